@@ -7,6 +7,42 @@ pub enum SchoolType {
     University,
 }
 
+impl SchoolType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SchoolType::HighSchool => "HighSchool",
+            SchoolType::College => "College",
+            SchoolType::University => "University",
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitMode {
+    FixedWindow,
+    SlidingWindow,
+    TokenBucket,
+}
+
+/// Day-to-day permissions the school authority can delegate to staff.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum StaffRole {
+    /// Manages courses, waitlists and student IDs.
+    Registrar,
+    /// Manages books and loans.
+    Librarian,
+    /// Revokes scholarships.
+    Bursar,
+    /// Grades students.
+    Instructor,
+}
+
+impl StaffRole {
+    pub fn mask(&self) -> u8 {
+        1 << *self as u8
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
 pub struct NftMetaDataAttributes {
     pub name: String,
@@ -16,5 +52,20 @@ pub struct NftMetaDataAttributes {
 
 pub const MAX_NAME_LENGTH: usize = 32;
 pub const MAX_SYMBOL_LENGTH: usize = 10;
+pub const STUDENT_ID_SYMBOL: &str = "SID";
+pub const MAX_WAITLIST_LENGTH: u16 = 50;
+pub const MAX_STAFF_MEMBERS: u16 = 20;
+pub const MAX_MULTISIG_SIGNERS: u8 = 10;
+pub const SECONDS_PER_DAY: u64 = 86_400;
+pub const MAX_INSTALLMENTS: u8 = 12;
+pub const MAX_PREREQUISITES: usize = 8;
+/// Grades are recorded out of 100.
+pub const MAX_GRADE: u8 = 100;
+pub const INSTALLMENT_INTERVAL: i64 = 30 * SECONDS_PER_DAY as i64;
+/// How long an installment can stay unpaid before the student is suspended.
+pub const INSTALLMENT_GRACE_PERIOD: i64 = 7 * SECONDS_PER_DAY as i64;
+/// Basis points in a whole, used for the enrollment fee multiplier.
+pub const BPS_DENOMINATOR: u64 = 10_000;
 pub const RATE_LIMIT_PERIOD: i64 = 3600;
 pub const RATE_LIMIT_COUNT: u64 = 5;
+pub const RATE_LIMIT_TOKEN_SCALE: u128 = 1_000_000;
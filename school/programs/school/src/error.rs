@@ -12,10 +12,194 @@ pub enum NFTError {
     InvalidURI,
     #[msg("Rate limit exceeded")]
     RateLimitExceeded,
+    #[msg("The rate limit window has not elapsed yet")]
+    RateLimitWindowActive,
+    #[msg("Rate limit period and quota must be greater than zero")]
+    InvalidRateLimitPolicy,
 }
 
 #[error_code]
 pub enum SchoolError {
     #[msg("Invalid school type")]
     InvalidSchoolType,
+    #[msg("Only the school authority can perform this action")]
+    Unauthorized,
+    #[msg("The school name is empty or too long")]
+    InvalidNameLength,
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    #[msg("Only the pending authority can accept the transfer")]
+    NotPendingAuthority,
+    #[msg("Fee tiers need 0 < minimum <= default <= maximum multiplier")]
+    InvalidFeeSchedule,
+    #[msg("The fee schedule has no tier for this school type")]
+    FeeScheduleNotConfigured,
+    #[msg("The fee multiplier is outside the school type's fee tier")]
+    FeeMultiplierOutOfRange,
+}
+
+#[error_code]
+pub enum CourseError {
+    #[msg("The course name is too long")]
+    InvalidNameLength,
+    #[msg("The course has reached its capacity")]
+    CourseFull,
+    #[msg("The course does not belong to this school")]
+    CourseNotInSchool,
+    #[msg("Students on the waitlist must be promoted first")]
+    WaitlistPending,
+    #[msg("The student is already registered for this course")]
+    AlreadyRegistered,
+    #[msg("The drop refund rate cannot exceed 100%")]
+    InvalidRefundRate,
+    #[msg("Tuition cannot be released before the add/drop deadline")]
+    AddDropWindowOpen,
+    #[msg("The enrollment holds no escrowed tuition")]
+    NothingEscrowed,
+    #[msg("The course has been cancelled")]
+    CourseCancelled,
+    #[msg("The course has not been cancelled")]
+    CourseNotCancelled,
+    #[msg("The enrollment record does not belong to this course")]
+    EnrollmentNotInCourse,
+    #[msg("The refund accounts do not match the enrollment record")]
+    InvalidRefundAccounts,
+    #[msg("The capacity cannot be lower than the number of enrolled students")]
+    CapacityBelowEnrollment,
+    #[msg("Only courses without enrolled students can be closed")]
+    CourseNotEmpty,
+    #[msg("The course has too many prerequisites")]
+    TooManyPrerequisites,
+    #[msg("Grades must be between 0 and 100")]
+    InvalidGrade,
+    #[msg("A course cannot be its own prerequisite")]
+    SelfPrerequisite,
+    #[msg("A completion record with a passing grade is required for every prerequisite")]
+    PrerequisiteNotMet,
+    #[msg("Prerequisites must be distinct courses of the same school, passed in order")]
+    InvalidPrerequisite,
+    #[msg("The student has already been graded in this course")]
+    AlreadyCompleted,
+}
+
+#[error_code]
+pub enum StudentError {
+    #[msg("The student has no active student ID")]
+    StudentIdNotIssued,
+    #[msg("Payment plans need between two and the maximum number of installments")]
+    InvalidInstallmentCount,
+    #[msg("Every installment of the payment plan has been paid")]
+    PaymentPlanCompleted,
+    #[msg("The student's payment plan account is required")]
+    PaymentPlanRequired,
+    #[msg("The student is suspended for an overdue installment")]
+    Suspended,
+}
+
+#[error_code]
+pub enum WaitlistError {
+    #[msg("Waitlist length must be between 1 and the maximum waitlist length")]
+    InvalidWaitlistLength,
+    #[msg("The waitlist deadline must be in the future")]
+    InvalidDeadline,
+    #[msg("The course still has free seats")]
+    CourseNotFull,
+    #[msg("The waitlist is full")]
+    WaitlistFull,
+    #[msg("The waitlist is empty")]
+    WaitlistEmpty,
+    #[msg("The student is already on the waitlist")]
+    AlreadyWaitlisted,
+    #[msg("The account is not next in line on the waitlist")]
+    NotNextInLine,
+    #[msg("The waitlist deadline has passed")]
+    DeadlinePassed,
+    #[msg("The waitlist deadline has not passed yet")]
+    DeadlineNotReached,
+}
+
+#[error_code]
+pub enum BookError {
+    #[msg("The book name is too long")]
+    InvalidNameLength,
+    #[msg("A book needs at least one copy")]
+    InvalidSupply,
+    #[msg("No copies of this book are available")]
+    NoCopiesAvailable,
+    #[msg("All copies of this book have already been returned")]
+    AllCopiesReturned,
+    #[msg("Edition number must be between 1 and the book's total supply")]
+    InvalidEdition,
+    #[msg("The book does not belong to this school")]
+    BookNotInSchool,
+    #[msg("Only the borrower can return this book")]
+    NotBorrower,
+    #[msg("The loan period must be greater than zero")]
+    InvalidLoanPeriod,
+    #[msg("The book is not overdue yet")]
+    NotOverdue,
+}
+
+#[error_code]
+pub enum StaffError {
+    #[msg("The staff registry is full")]
+    StaffRegistryFull,
+    #[msg("The member does not hold this role")]
+    RoleNotHeld,
+}
+
+#[error_code]
+pub enum MultisigError {
+    #[msg("Signers must be unique and between one and the maximum signer count")]
+    InvalidSigners,
+    #[msg("The threshold must be between one and the number of signers")]
+    InvalidThreshold,
+    #[msg("The signer is not part of the multisig")]
+    NotASigner,
+    #[msg("The signer has already approved this proposal")]
+    AlreadyApproved,
+    #[msg("The proposal does not have enough approvals")]
+    NotEnoughApprovals,
+    #[msg("The recipient does not match the proposal")]
+    InvalidRecipient,
+    #[msg("An account the proposal needs is missing or does not match it")]
+    ProposalAccountMismatch,
+    #[msg("The proposal does not fit in a proposal account")]
+    ProposalTooLarge,
+}
+
+#[error_code]
+pub enum TreasuryError {
+    #[msg("The amount must be greater than zero")]
+    InvalidAmount,
+    #[msg("The treasury does not hold enough lamports")]
+    InsufficientFunds,
+    #[msg("The mint is not the school's payment mint")]
+    InvalidPaymentMint,
+    #[msg("Token accounts are required to pay in the school's payment mint")]
+    MissingPaymentAccounts,
+    #[msg("The treasury token account does not belong to the treasury")]
+    InvalidTreasuryTokenAccount,
+    #[msg("Tokens collected in the current payment mint have not all been withdrawn")]
+    TokenBalanceOutstanding,
+    #[msg("The escrow token account is not the escrow holder's associated token account")]
+    InvalidEscrowTokenAccount,
+    #[msg("Refunds must go to a token account owned by the student's wallet")]
+    InvalidRefundTokenAccount,
+}
+
+#[error_code]
+pub enum ScholarshipError {
+    #[msg("Percentages must be between 1 and 10000 bps and fixed amounts above zero")]
+    InvalidDiscount,
+    #[msg("The expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("The scholarship does not cover this fee")]
+    NotApplicable,
+    #[msg("The scholarship has expired")]
+    Expired,
+    #[msg("The scholarship has already been used")]
+    AlreadyRedeemed,
+    #[msg("The course is not the scholarship's course or belongs to another school")]
+    InvalidCourse,
 }
@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::StaffRole, ProposalAction};
+
+#[event]
+pub struct EnrollmentFeeUpdated {
+    pub school: Pubkey,
+    pub old_fee: u64,
+    pub new_fee: u64,
+}
+
+#[event]
+pub struct FeeMultiplierUpdated {
+    pub school: Pubkey,
+    pub old_multiplier: u64,
+    pub new_multiplier: u64,
+}
+
+#[event]
+pub struct SchoolNameUpdated {
+    pub school: Pubkey,
+    pub old_name: String,
+    pub new_name: String,
+}
+
+#[event]
+pub struct AuthorityTransferProposed {
+    pub school: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub school: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferAccepted {
+    pub school: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct StaffRoleGranted {
+    pub school: Pubkey,
+    pub member: Pubkey,
+    pub role: StaffRole,
+}
+
+#[event]
+pub struct StaffRoleRevoked {
+    pub school: Pubkey,
+    pub member: Pubkey,
+    pub role: StaffRole,
+}
+
+#[event]
+pub struct MultisigCreated {
+    pub school: Pubkey,
+    pub multisig: Pubkey,
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+}
+
+#[event]
+pub struct ProposalApproved {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub signer: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+}
+
+#[event]
+pub struct TreasuryWithdrawn {
+    pub school: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+}
+
+#[event]
+pub struct PaymentMintUpdated {
+    pub school: Pubkey,
+    pub old_mint: Option<Pubkey>,
+    pub new_mint: Option<Pubkey>,
+}
+
+#[event]
+pub struct TreasuryTokensWithdrawn {
+    pub school: Pubkey,
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+}
+
+#[event]
+pub struct ScholarshipRedeemed {
+    pub scholarship: Pubkey,
+    pub wallet: Pubkey,
+    pub fee: u64,
+    pub waived: u64,
+}
+
+#[event]
+pub struct TuitionSettled {
+    pub enrollment: Pubkey,
+    pub wallet: Pubkey,
+    pub refunded: u64,
+    pub released: u64,
+}
+
+#[event]
+pub struct CourseCancelled {
+    pub school: Pubkey,
+    pub course: Pubkey,
+    pub enrolled_students: u32,
+}
+
+#[event]
+pub struct CourseRefundSkipped {
+    pub course: Pubkey,
+    pub enrollment: Pubkey,
+}
+
+#[event]
+pub struct CourseUpdated {
+    pub course: Pubkey,
+    pub name: String,
+    pub capacity: u32,
+    pub tuition_fee: u64,
+}
+
+#[event]
+pub struct CourseClosed {
+    pub school: Pubkey,
+    pub course: Pubkey,
+}
+
+#[event]
+pub struct CourseCompleted {
+    pub course: Pubkey,
+    pub student: Pubkey,
+    pub grade: u8,
+    pub graded_by: Pubkey,
+}
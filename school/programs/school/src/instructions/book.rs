@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use super::mintnft::*;
+use crate::{
+    constants::{StaffRole, MAX_NAME_LENGTH},
+    error::{BookError, SchoolError},
+    Book, BookTrait, School, SchoolTrait, StaffRegistry, StaffRegistryTrait,
+};
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct AddBook<'info> {
+    pub nft: InitNFT<'info>,
+    #[account(
+        mut,
+        address = nft.school.key()
+    )]
+    pub school: Account<'info, School>,
+    #[account(
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump = staff_registry.get_bump(),
+        constraint = staff_registry.authorizes(&school, &nft.authority.key(), StaffRole::Librarian)
+            @ SchoolError::Unauthorized
+    )]
+    pub staff_registry: Account<'info, StaffRegistry>,
+    #[account(
+        init,
+        payer = nft.authority,
+        space = Book::space(name.len()),
+        seeds = [b"book", school.key().as_ref(), &school.book_count().to_le_bytes()],
+        bump
+    )]
+    pub book: Account<'info, Book>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Adds a book to the school catalogue and mints the master edition NFT for its title.
+///
+/// The book is addressed by the school's current `book_count` and starts with all
+/// `total_supply` copies available. The master edition can print at most `total_supply`
+/// editions, one per copy on loan.
+pub fn add_book(
+    ctx: Context<AddBook>,
+    name: String,
+    symbol: String,
+    uri: String,
+    total_supply: u64,
+) -> Result<()> {
+    require!(name.len() <= MAX_NAME_LENGTH, BookError::InvalidNameLength);
+    require!(total_supply > 0, BookError::InvalidSupply);
+    ctx.accounts.nft.mint_with_metadata(
+        &ctx.bumps.nft,
+        &name,
+        &symbol,
+        &uri,
+        Some(total_supply),
+    )?;
+
+    let school = &mut ctx.accounts.school;
+    ctx.accounts.book.set_inner(Book::new(
+        name,
+        school.key(),
+        ctx.accounts.nft.mint.key(),
+        total_supply,
+        ctx.bumps.book,
+    ));
+    school.increment_book_count()
+}
@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+
+use super::tuition::*;
+use crate::{
+    constants::{StaffRole, MAX_GRADE},
+    error::{CourseError, SchoolError},
+    events::CourseCompleted,
+    CompletionRecord, CompletionRecordTrait, Course, CourseEnrollment, CourseEnrollmentTrait,
+    CourseTrait, School, StaffRegistry, StaffRegistryTrait, Student, StudentTrait, Treasury,
+    TreasuryTrait,
+};
+
+/// Fails unless `completion_records` hold, in the same order as the course's prerequisites,
+/// a record of the student passing each of them with at least the course's minimum grade.
+pub(crate) fn require_prerequisites<'info>(
+    course: &Course,
+    student: Pubkey,
+    completion_records: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    let prerequisites = course.get_prerequisites();
+    require!(
+        completion_records.len() >= prerequisites.len(),
+        CourseError::PrerequisiteNotMet
+    );
+    for (prerequisite, record) in prerequisites.iter().zip(completion_records) {
+        let record = Account::<CompletionRecord>::try_from(record)?;
+        require!(
+            record.get_course() == *prerequisite
+                && record.get_student() == student
+                && record.get_grade() >= course.get_min_grade(),
+            CourseError::PrerequisiteNotMet
+        );
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RecordCompletion<'info> {
+    #[account(mut)]
+    pub instructor: Signer<'info>,
+    pub school: Account<'info, School>,
+    #[account(
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump = staff_registry.get_bump(),
+        constraint = staff_registry.authorizes(&school, &instructor.key(), StaffRole::Instructor)
+            @ SchoolError::Unauthorized
+    )]
+    pub staff_registry: Account<'info, StaffRegistry>,
+    #[account(
+        constraint = course.get_school() == school.key() @ CourseError::CourseNotInSchool
+    )]
+    pub course: Account<'info, Course>,
+    pub student: Account<'info, Student>,
+    #[account(
+        seeds = [b"course_enrollment", course.key().as_ref(), student.key().as_ref()],
+        bump = enrollment.get_bump()
+    )]
+    pub enrollment: Account<'info, CourseEnrollment>,
+    #[account(
+        init_if_needed,
+        payer = instructor,
+        space = CompletionRecord::LEN,
+        seeds = [b"completion", course.key().as_ref(), student.key().as_ref()],
+        bump
+    )]
+    pub completion_record: Account<'info, CompletionRecord>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Records the final grade of a student registered for the course, signed by the authority
+/// or an instructor.
+///
+/// Recording again overwrites the previous grade, until `complete_enrollment` closes the
+/// student's registration.
+pub fn record_completion(ctx: Context<RecordCompletion>, grade: u8) -> Result<()> {
+    require!(grade <= MAX_GRADE, CourseError::InvalidGrade);
+    let course = ctx.accounts.course.key();
+    let student = ctx.accounts.student.key();
+    let graded_by = ctx.accounts.instructor.key();
+    ctx.accounts
+        .completion_record
+        .set_inner(CompletionRecord::new(
+            course,
+            student,
+            grade,
+            graded_by,
+            Clock::get()?.unix_timestamp,
+            ctx.bumps.completion_record,
+        ));
+    emit!(CourseCompleted {
+        course,
+        student,
+        grade,
+        graded_by,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CompleteEnrollment<'info> {
+    pub school: Account<'info, School>,
+    #[account(
+        mut,
+        constraint = course.get_school() == school.key() @ CourseError::CourseNotInSchool,
+        constraint = !course.is_cancelled() @ CourseError::CourseCancelled
+    )]
+    pub course: Account<'info, Course>,
+    #[account(
+        seeds = [b"student", school.key().as_ref(), wallet.key().as_ref()],
+        bump = student.get_bump()
+    )]
+    pub student: Account<'info, Student>,
+    #[account(mut, address = student.get_wallet())]
+    pub wallet: SystemAccount<'info>,
+    #[account(
+        mut,
+        close = wallet,
+        seeds = [b"course_enrollment", course.key().as_ref(), student.key().as_ref()],
+        bump = enrollment.get_bump()
+    )]
+    pub enrollment: Account<'info, CourseEnrollment>,
+    #[account(
+        seeds = [b"completion", course.key().as_ref(), student.key().as_ref()],
+        bump = completion_record.get_bump()
+    )]
+    pub completion_record: Account<'info, CompletionRecord>,
+    #[account(
+        mut,
+        seeds = [b"treasury", school.key().as_ref()],
+        bump = treasury.get_bump()
+    )]
+    pub treasury: Account<'info, Treasury>,
+    pub escrow: TuitionEscrow<'info>,
+}
+
+/// Closes the registration of a graded student, freeing their seat so that the course can
+/// eventually be closed.
+///
+/// Anyone can crank this. Tuition still in escrow is released to the treasury, since the
+/// course has been completed, and the enrollment record's rent goes back to the student.
+/// The completion record is kept as proof for prerequisites.
+pub fn complete_enrollment(ctx: Context<CompleteEnrollment>) -> Result<()> {
+    ctx.accounts.escrow.settle(
+        &ctx.accounts.school,
+        &mut ctx.accounts.enrollment,
+        &mut ctx.accounts.treasury,
+        &ctx.accounts.wallet.to_account_info(),
+        0,
+    )?;
+    ctx.accounts.course.decrement_enrolled_students_count()
+}
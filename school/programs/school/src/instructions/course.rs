@@ -0,0 +1,386 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    metadata::{burn_nft, BurnNft, Metadata},
+    token::{close_account, CloseAccount, Mint, Token, TokenAccount},
+};
+use mpl_token_metadata::accounts::{MasterEdition, Metadata as MetaDataAccount};
+
+use super::mintnft::*;
+use crate::{
+    constants::{StaffRole, MAX_NAME_LENGTH},
+    error::{CourseError, SchoolError},
+    events::{CourseCancelled, CourseClosed, CourseUpdated},
+    Course, CourseTrait, School, SchoolTrait, StaffRegistry, StaffRegistryTrait, Treasury,
+    TreasuryTrait,
+};
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct AddCourse<'info> {
+    pub nft: InitNFT<'info>,
+    #[account(
+        mut,
+        address = nft.school.key()
+    )]
+    pub school: Account<'info, School>,
+    #[account(
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump = staff_registry.get_bump(),
+        constraint = staff_registry.authorizes(&school, &nft.authority.key(), StaffRole::Registrar)
+            @ SchoolError::Unauthorized
+    )]
+    pub staff_registry: Account<'info, StaffRegistry>,
+    #[account(
+        init,
+        payer = nft.authority,
+        space = Course::space(name.len(), 0),
+        seeds = [b"course", school.key().as_ref(), &school.next_course_index().to_le_bytes()],
+        bump
+    )]
+    pub course: Account<'info, Course>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates a course PDA under the school and mints the course NFT into the school's custody.
+///
+/// The course and its NFT belong to the school rather than to the registrar who added them,
+/// so they stay with the school when staff change or the authority is transferred. The
+/// course is addressed by the school's `next_course_index`, which is incremented afterwards
+/// along with `class_count` so that the next course gets a fresh index.
+pub fn add_course(
+    ctx: Context<AddCourse>,
+    name: String,
+    symbol: String,
+    uri: String,
+    capacity: u32,
+    tuition_fee: u64,
+) -> Result<()> {
+    require!(
+        name.len() <= MAX_NAME_LENGTH,
+        CourseError::InvalidNameLength
+    );
+    ctx.accounts
+        .nft
+        .mint_with_metadata(&ctx.bumps.nft, &name, &symbol, &uri, None)?;
+
+    let school = &mut ctx.accounts.school;
+    let nft = &ctx.accounts.nft;
+    ctx.accounts.course.set_inner(Course::new(
+        name,
+        nft.mint.key(),
+        capacity,
+        0,
+        tuition_fee,
+        school.key(),
+        school.key(),
+        nft.associated_token_account.key(),
+        ctx.bumps.course,
+    ));
+    school.increment_class_count()
+}
+
+#[derive(Accounts)]
+pub struct UpdateAddDropPolicy<'info> {
+    pub authority: Signer<'info>,
+    pub school: Account<'info, School>,
+    #[account(
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump = staff_registry.get_bump(),
+        constraint = staff_registry.authorizes(&school, &authority.key(), StaffRole::Registrar)
+            @ SchoolError::Unauthorized
+    )]
+    pub staff_registry: Account<'info, StaffRegistry>,
+    #[account(
+        mut,
+        constraint = course.get_school() == school.key() @ CourseError::CourseNotInSchool
+    )]
+    pub course: Account<'info, Course>,
+}
+
+/// Sets the course's add/drop deadline and the share of escrowed tuition, in basis points,
+/// refunded to students who drop before it.
+///
+/// New courses refund in full but have no add/drop window, so their tuition can be released
+/// to the treasury right away. Students already registered keep the policy they registered
+/// under.
+pub fn update_add_drop_policy(
+    ctx: Context<UpdateAddDropPolicy>,
+    add_drop_deadline: i64,
+    drop_refund_bps: u16,
+) -> Result<()> {
+    ctx.accounts
+        .course
+        .set_add_drop_policy(add_drop_deadline, drop_refund_bps)
+}
+
+#[derive(Accounts)]
+pub struct CancelCourse<'info> {
+    pub authority: Signer<'info>,
+    pub school: Account<'info, School>,
+    #[account(
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump = staff_registry.get_bump(),
+        constraint = staff_registry.authorizes(&school, &authority.key(), StaffRole::Registrar)
+            @ SchoolError::Unauthorized
+    )]
+    pub staff_registry: Account<'info, StaffRegistry>,
+    #[account(
+        mut,
+        constraint = course.get_school() == school.key() @ CourseError::CourseNotInSchool,
+        constraint = !course.is_cancelled() @ CourseError::CourseCancelled
+    )]
+    pub course: Account<'info, Course>,
+}
+
+/// Cancels the course, closing it to new registrations and waitlist entries.
+///
+/// Enrolled students are refunded by cranking `process_course_refunds`, and waitlisted
+/// students can be refunded with `refund_waitlist` without waiting for its deadline.
+pub fn cancel_course(ctx: Context<CancelCourse>) -> Result<()> {
+    let course = &mut ctx.accounts.course;
+    course.cancel();
+    emit!(CourseCancelled {
+        school: ctx.accounts.school.key(),
+        course: course.key(),
+        enrolled_students: course.get_enrolled_students_count(),
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(name: Option<String>)]
+pub struct UpdateCourse<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub school: Account<'info, School>,
+    #[account(
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump = staff_registry.get_bump(),
+        constraint = staff_registry.authorizes(&school, &authority.key(), StaffRole::Registrar)
+            @ SchoolError::Unauthorized
+    )]
+    pub staff_registry: Account<'info, StaffRegistry>,
+    #[account(
+        mut,
+        constraint = course.get_school() == school.key() @ CourseError::CourseNotInSchool,
+        realloc = Course::space(
+            name.as_deref().unwrap_or(course.get_name()).len(),
+            course.get_prerequisites().len()
+        ),
+        realloc::payer = authority,
+        realloc::zero = false
+    )]
+    pub course: Account<'info, Course>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Updates whichever of the course's name, capacity and tuition fee are given.
+///
+/// The capacity cannot drop below the number of enrolled students. A new tuition fee only
+/// applies to later registrations and waitlist entries.
+pub fn update_course(
+    ctx: Context<UpdateCourse>,
+    name: Option<String>,
+    capacity: Option<u32>,
+    tuition_fee: Option<u64>,
+) -> Result<()> {
+    let course = &mut ctx.accounts.course;
+    if let Some(name) = name {
+        require!(
+            !name.is_empty() && name.len() <= MAX_NAME_LENGTH,
+            CourseError::InvalidNameLength
+        );
+        course.set_name(name);
+    }
+    if let Some(capacity) = capacity {
+        require!(
+            capacity >= course.get_enrolled_students_count(),
+            CourseError::CapacityBelowEnrollment
+        );
+        course.set_capacity(capacity);
+    }
+    if let Some(tuition_fee) = tuition_fee {
+        course.set_tution_fee(tuition_fee);
+    }
+    emit!(CourseUpdated {
+        course: course.key(),
+        name: course.get_name().to_string(),
+        capacity: course.get_capacity(),
+        tuition_fee: course.get_tution_fee(),
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseCourse<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub school: Account<'info, School>,
+    #[account(
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump = staff_registry.get_bump(),
+        constraint = staff_registry.authorizes(&school, &authority.key(), StaffRole::Registrar)
+            @ SchoolError::Unauthorized
+    )]
+    pub staff_registry: Account<'info, StaffRegistry>,
+    #[account(
+        mut,
+        close = treasury,
+        constraint = course.get_school() == school.key() @ CourseError::CourseNotInSchool,
+        constraint = course.get_enrolled_students_count() == 0 @ CourseError::CourseNotEmpty,
+        constraint = course.get_waitlist_count() == 0 @ CourseError::WaitlistPending
+    )]
+    pub course: Account<'info, Course>,
+    /// Receives the rent of everything closed, since the course belongs to the school.
+    #[account(
+        mut,
+        seeds = [b"treasury", school.key().as_ref()],
+        bump = treasury.get_bump()
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut, address = course.get_nft_mint())]
+    pub nft_mint: Box<Account<'info, Mint>>,
+    #[account(mut, address = course.get_nft_token_account())]
+    pub nft_token_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK: Address is derived using a known PDA
+    #[account(mut, address = MetaDataAccount::find_pda(&nft_mint.key()).0)]
+    pub metadata: AccountInfo<'info>,
+    /// CHECK: Address is derived using a known PDA
+    #[account(mut, address = MasterEdition::find_pda(&nft_mint.key()).0)]
+    pub master_edition: AccountInfo<'info>,
+    /// CHECK: The course's waitlist PDA, closed here if the course ever had one.
+    #[account(mut, seeds = [b"waitlist", course.key().as_ref()], bump)]
+    pub waitlist: UncheckedAccount<'info>,
+    /// The waitlist's token account for an SPL payment mint, if it has one.
+    #[account(mut, token::authority = waitlist)]
+    pub waitlist_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    pub token_program: Program<'info, Token>,
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+/// Closes a course nobody is enrolled in or waiting for, along with its NFT and waitlist,
+/// and moves all of their rent to the treasury, where it is recorded as a deposit.
+///
+/// The course NFT is burned through Metaplex, which closes its token, metadata and master
+/// edition accounts; only its empty mint remains, as SPL mints cannot be closed. The school's
+/// class count goes down, but the index of a closed course is never reused.
+pub fn close_course(ctx: Context<CloseCourse>) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    let treasury_lamports = accounts.treasury.get_lamports();
+    let school_lamports = accounts.school.get_lamports();
+    let seeds = accounts.school.signer_seeds();
+    burn_nft(
+        CpiContext::new_with_signer(
+            accounts.token_metadata_program.to_account_info(),
+            BurnNft {
+                metadata: accounts.metadata.to_account_info(),
+                owner: accounts.school.to_account_info(),
+                mint: accounts.nft_mint.to_account_info(),
+                token: accounts.nft_token_account.to_account_info(),
+                edition: accounts.master_edition.to_account_info(),
+                spl_token: accounts.token_program.to_account_info(),
+            },
+            &[&seeds[..]],
+        ),
+        None,
+    )?;
+    let reclaimed = accounts
+        .school
+        .get_lamports()
+        .checked_sub(school_lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    accounts.school.sub_lamports(reclaimed)?;
+    accounts.treasury.add_lamports(reclaimed)?;
+
+    let course = accounts.course.key();
+    if let Some(waitlist_token_account) = &accounts.waitlist_token_account {
+        close_account(CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: waitlist_token_account.to_account_info(),
+                destination: accounts.treasury.to_account_info(),
+                authority: accounts.waitlist.to_account_info(),
+            },
+            &[&[b"waitlist", course.as_ref(), &[ctx.bumps.waitlist]]],
+        ))?;
+    }
+    let waitlist = accounts.waitlist.to_account_info();
+    if waitlist.owner == &crate::ID {
+        let lamports = waitlist.lamports();
+        waitlist.sub_lamports(lamports)?;
+        accounts.treasury.add_lamports(lamports)?;
+        waitlist.assign(&system_program::ID);
+        waitlist.realloc(0, false)?;
+    }
+    // The course account itself is closed to the treasury once the instruction returns.
+    let reclaimed = accounts
+        .treasury
+        .get_lamports()
+        .checked_sub(treasury_lamports)
+        .and_then(|reclaimed| reclaimed.checked_add(accounts.course.get_lamports()))
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    accounts.treasury.record_deposit(reclaimed)?;
+
+    accounts.school.decrement_class_count()?;
+    emit!(CourseClosed {
+        school: accounts.school.key(),
+        course,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(prerequisites: Vec<Pubkey>)]
+pub struct UpdatePrerequisites<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub school: Account<'info, School>,
+    #[account(
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump = staff_registry.get_bump(),
+        constraint = staff_registry.authorizes(&school, &authority.key(), StaffRole::Registrar)
+            @ SchoolError::Unauthorized
+    )]
+    pub staff_registry: Account<'info, StaffRegistry>,
+    #[account(
+        mut,
+        constraint = course.get_school() == school.key() @ CourseError::CourseNotInSchool,
+        realloc = Course::space(course.get_name().len(), prerequisites.len()),
+        realloc::payer = authority,
+        realloc::zero = false
+    )]
+    pub course: Account<'info, Course>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Replaces the courses a student must have completed with at least `min_grade` before
+/// registering for this one.
+///
+/// The prerequisite courses are passed, in the same order, as remaining accounts, so that
+/// each can be checked to be a distinct course of the same school.
+pub fn update_prerequisites<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdatePrerequisites<'info>>,
+    prerequisites: Vec<Pubkey>,
+    min_grade: u8,
+) -> Result<()> {
+    let course = &mut ctx.accounts.course;
+    require!(
+        !prerequisites.contains(&course.key()),
+        CourseError::SelfPrerequisite
+    );
+    require!(
+        ctx.remaining_accounts.len() == prerequisites.len(),
+        CourseError::InvalidPrerequisite
+    );
+    for (i, (prerequisite, account)) in prerequisites.iter().zip(ctx.remaining_accounts).enumerate()
+    {
+        let account = Account::<Course>::try_from(account)?;
+        require!(
+            account.key() == *prerequisite
+                && account.get_school() == ctx.accounts.school.key()
+                && !prerequisites[..i].contains(prerequisite),
+            CourseError::InvalidPrerequisite
+        );
+    }
+    course.set_prerequisites(prerequisites, min_grade)
+}
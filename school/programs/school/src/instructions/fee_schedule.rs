@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::SchoolType, error::SchoolError, program::School as SchoolProgram, FeeSchedule,
+    FeeScheduleTrait, FeeTier,
+};
+
+#[derive(Accounts)]
+pub struct UpdateFeeSchedule<'info> {
+    /// Upgrade authority of the program.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, SchoolProgram>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ SchoolError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = FeeSchedule::LEN,
+        seeds = [b"fee_schedule"],
+        bump
+    )]
+    pub fee_schedule: Account<'info, FeeSchedule>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Sets the default multiplier and its bounds for one type of school.
+///
+/// Existing schools keep their multiplier; the new bounds apply the next time it is changed.
+pub fn update_fee_schedule(
+    ctx: Context<UpdateFeeSchedule>,
+    school_type: SchoolType,
+    tier: FeeTier,
+) -> Result<()> {
+    ctx.accounts
+        .fee_schedule
+        .set_tier(school_type, tier, ctx.bumps.fee_schedule)
+}
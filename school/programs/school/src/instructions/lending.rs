@@ -0,0 +1,514 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{
+        burn_edition_nft, freeze_delegated_account, mint_new_edition_from_master_edition_via_token,
+        thaw_delegated_account, BurnEditionNft, FreezeDelegatedAccount, Metadata,
+        MintNewEditionFromMasterEditionViaToken, ThawDelegatedAccount,
+    },
+    token::{approve, mint_to, transfer, Approve, Mint, MintTo, Token, TokenAccount, Transfer},
+};
+use mpl_token_metadata::accounts::{EditionMarker, MasterEdition, Metadata as MetaDataAccount};
+
+use super::treasury::deposit;
+use crate::{
+    constants::StaffRole,
+    error::{BookError, SchoolError, StudentError},
+    Book, BookLoan, BookLoanTrait, BookTrait, LibraryConfig, LibraryConfigTrait, School,
+    StaffRegistry, StaffRegistryTrait, Student, StudentTrait, Treasury, TreasuryTrait,
+};
+
+/// Metaplex tracks printed editions in marker accounts of 248 editions each.
+const EDITION_MARKER_BIT_SIZE: u64 = 248;
+
+#[derive(Accounts)]
+pub struct UpdateLibraryConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub school: Account<'info, School>,
+    #[account(
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump = staff_registry.get_bump(),
+        constraint = staff_registry.authorizes(&school, &authority.key(), StaffRole::Librarian)
+            @ SchoolError::Unauthorized
+    )]
+    pub staff_registry: Account<'info, StaffRegistry>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = LibraryConfig::LEN,
+        seeds = [b"library_config", school.key().as_ref()],
+        bump
+    )]
+    pub library_config: Account<'info, LibraryConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Sets how long books can be borrowed and the late fee charged per started day overdue.
+///
+/// Late fees are always charged in lamports, whatever the school's payment mint.
+pub fn update_library_config(
+    ctx: Context<UpdateLibraryConfig>,
+    loan_period: i64,
+    late_fee_per_day: u64,
+) -> Result<()> {
+    let school = ctx.accounts.school.key();
+    ctx.accounts.library_config.update(
+        school,
+        loan_period,
+        late_fee_per_day,
+        ctx.bumps.library_config,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(edition: u64)]
+pub struct RequestBook<'info> {
+    /// The librarian lending the copy.
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    pub school: Box<Account<'info, School>>,
+    #[account(
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump = staff_registry.get_bump(),
+        constraint = staff_registry.authorizes(&school, &authority.key(), StaffRole::Librarian)
+            @ SchoolError::Unauthorized
+    )]
+    pub staff_registry: Box<Account<'info, StaffRegistry>>,
+    #[account(
+        seeds = [b"student", school.key().as_ref(), wallet.key().as_ref()],
+        bump = student.get_bump(),
+        constraint = student.has_student_id() @ StudentError::StudentIdNotIssued
+    )]
+    pub student: Box<Account<'info, Student>>,
+    #[account(mut, constraint = book.get_school() == school.key() @ BookError::BookNotInSchool)]
+    pub book: Box<Account<'info, Book>>,
+    #[account(
+        seeds = [b"library_config", school.key().as_ref()],
+        bump = library_config.get_bump()
+    )]
+    pub library_config: Box<Account<'info, LibraryConfig>>,
+    #[account(address = book.get_nft_mint())]
+    pub master_mint: Box<Account<'info, Mint>>,
+    #[account(
+        associated_token::mint = master_mint,
+        associated_token::authority = school
+    )]
+    pub master_token_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK: Address is derived using a known PDA
+    #[account(address = MetaDataAccount::find_pda(&master_mint.key()).0)]
+    pub master_metadata: AccountInfo<'info>,
+    /// CHECK: Address is derived using a known PDA
+    #[account(mut, address = MasterEdition::find_pda(&master_mint.key()).0)]
+    pub master_edition: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = wallet,
+        mint::decimals = 0,
+        mint::authority = school.key(),
+        mint::freeze_authority = school.key()
+    )]
+    pub edition_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = wallet,
+        associated_token::mint = edition_mint,
+        associated_token::authority = wallet
+    )]
+    pub edition_token_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK: Address is derived using a known PDA
+    #[account(mut, address = MetaDataAccount::find_pda(&edition_mint.key()).0)]
+    pub edition_metadata: AccountInfo<'info>,
+    /// CHECK: Address is derived using a known PDA
+    #[account(mut, address = MasterEdition::find_pda(&edition_mint.key()).0)]
+    pub edition_account: AccountInfo<'info>,
+    /// CHECK: Address is derived using a known PDA
+    #[account(
+        mut,
+        address = EditionMarker::find_pda(
+            &master_mint.key(),
+            &(edition / EDITION_MARKER_BIT_SIZE).to_string()
+        ).0
+    )]
+    pub edition_marker: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = wallet,
+        space = BookLoan::LEN,
+        seeds = [b"book_loan", book.key().as_ref(), edition_mint.key().as_ref()],
+        bump
+    )]
+    pub loan: Box<Account<'info, BookLoan>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Lends a copy of a book to a student by printing edition number `edition` of its master edition.
+///
+/// The edition must be between 1 and the book's `total_supply` and not currently printed;
+/// Metaplex rejects an edition number that is already in use. The loan is due after the
+/// school's loan period. The student delegates the edition to the school, which freezes it
+/// for the whole loan: the copy can neither be moved nor have its delegation revoked, so it
+/// can only leave the wallet through `return_book` or `reclaim_overdue_book`.
+pub fn request_book(ctx: Context<RequestBook>, edition: u64) -> Result<()> {
+    let accounts = &ctx.accounts;
+    require!(
+        edition >= 1 && edition <= accounts.book.get_total_supply(),
+        BookError::InvalidEdition
+    );
+
+    // The school holds the master edition, so it signs the print.
+    let seeds = accounts.school.signer_seeds();
+    let signer = &[&seeds[..]];
+    let school = accounts.school.to_account_info();
+    mint_to(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            MintTo {
+                mint: accounts.edition_mint.to_account_info(),
+                to: accounts.edition_token_account.to_account_info(),
+                authority: school.clone(),
+            },
+            signer,
+        ),
+        1,
+    )?;
+
+    mint_new_edition_from_master_edition_via_token(
+        CpiContext::new_with_signer(
+            accounts.token_metadata_program.to_account_info(),
+            MintNewEditionFromMasterEditionViaToken {
+                new_metadata: accounts.edition_metadata.to_account_info(),
+                new_edition: accounts.edition_account.to_account_info(),
+                master_edition: accounts.master_edition.to_account_info(),
+                new_mint: accounts.edition_mint.to_account_info(),
+                edition_mark_pda: accounts.edition_marker.to_account_info(),
+                new_mint_authority: school.clone(),
+                payer: accounts.wallet.to_account_info(),
+                token_account_owner: school.clone(),
+                token_account: accounts.master_token_account.to_account_info(),
+                new_metadata_update_authority: school.clone(),
+                metadata: accounts.master_metadata.to_account_info(),
+                token_program: accounts.token_program.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+                rent: accounts.rent.to_account_info(),
+                metadata_mint: accounts.master_mint.to_account_info(),
+            },
+            signer,
+        ),
+        edition,
+    )?;
+
+    approve(
+        CpiContext::new(
+            accounts.token_program.to_account_info(),
+            Approve {
+                to: accounts.edition_token_account.to_account_info(),
+                delegate: school.clone(),
+                authority: accounts.wallet.to_account_info(),
+            },
+        ),
+        1,
+    )?;
+
+    freeze_delegated_account(CpiContext::new_with_signer(
+        accounts.token_metadata_program.to_account_info(),
+        FreezeDelegatedAccount {
+            metadata: accounts.edition_metadata.to_account_info(),
+            delegate: school,
+            token_account: accounts.edition_token_account.to_account_info(),
+            edition: accounts.edition_account.to_account_info(),
+            mint: accounts.edition_mint.to_account_info(),
+            token_program: accounts.token_program.to_account_info(),
+        },
+        signer,
+    ))?;
+
+    let clock = Clock::get()?;
+    let due_at = clock
+        .unix_timestamp
+        .checked_add(accounts.library_config.get_loan_period())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    ctx.accounts.book.checkout_copy()?;
+    let loan = BookLoan::new(
+        ctx.accounts.book.key(),
+        ctx.accounts.student.key(),
+        ctx.accounts.wallet.key(),
+        ctx.accounts.edition_mint.key(),
+        edition,
+        clock.unix_timestamp,
+        due_at,
+        ctx.bumps.loan,
+    );
+    ctx.accounts.loan.set_inner(loan);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReturnBook<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    #[account(mut, constraint = book.get_school() == school.key() @ BookError::BookNotInSchool)]
+    pub book: Box<Account<'info, Book>>,
+    pub school: Box<Account<'info, School>>,
+    #[account(
+        seeds = [b"library_config", school.key().as_ref()],
+        bump = library_config.get_bump()
+    )]
+    pub library_config: Box<Account<'info, LibraryConfig>>,
+    #[account(
+        mut,
+        seeds = [b"treasury", school.key().as_ref()],
+        bump = treasury.get_bump()
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+    #[account(
+        mut,
+        close = wallet,
+        seeds = [b"book_loan", book.key().as_ref(), edition_mint.key().as_ref()],
+        bump = loan.get_bump(),
+        constraint = loan.get_wallet() == wallet.key() @ BookError::NotBorrower
+    )]
+    pub loan: Box<Account<'info, BookLoan>>,
+    #[account(address = book.get_nft_mint())]
+    pub master_mint: Box<Account<'info, Mint>>,
+    #[account(
+        associated_token::mint = master_mint,
+        associated_token::authority = school,
+        constraint = master_token_account.amount == 1
+    )]
+    pub master_token_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK: Address is derived using a known PDA
+    #[account(mut, address = MasterEdition::find_pda(&master_mint.key()).0)]
+    pub master_edition: AccountInfo<'info>,
+    #[account(mut)]
+    pub edition_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = edition_mint,
+        associated_token::authority = wallet
+    )]
+    pub edition_token_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK: Address is derived using a known PDA
+    #[account(mut, address = MetaDataAccount::find_pda(&edition_mint.key()).0)]
+    pub edition_metadata: AccountInfo<'info>,
+    /// CHECK: Address is derived using a known PDA
+    #[account(mut, address = MasterEdition::find_pda(&edition_mint.key()).0)]
+    pub edition_account: AccountInfo<'info>,
+    /// CHECK: Address is derived using a known PDA
+    #[account(
+        mut,
+        address = EditionMarker::find_pda(
+            &master_mint.key(),
+            &(loan.get_edition() / EDITION_MARKER_BIT_SIZE).to_string()
+        ).0
+    )]
+    pub edition_marker: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Returns a borrowed book by burning its printed edition.
+///
+/// Returning after the due date charges the school's late fee into the treasury. The edition
+/// has been frozen since checkout, so the school thaws it before the student burns it.
+/// Burning through Metaplex frees the edition number and decrements the master edition
+/// supply, so the copy can be lent again. The rent of the edition accounts and the loan
+/// record goes back to the student.
+pub fn return_book(ctx: Context<ReturnBook>) -> Result<()> {
+    let clock = Clock::get()?;
+    let late_fee = ctx
+        .accounts
+        .library_config
+        .calculate_late_fee(ctx.accounts.loan.get_due_at(), clock.unix_timestamp)?;
+    deposit(
+        &mut ctx.accounts.treasury,
+        ctx.accounts.wallet.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        late_fee,
+    )?;
+
+    let accounts = &ctx.accounts;
+
+    let seeds = accounts.school.signer_seeds();
+    thaw_delegated_account(CpiContext::new_with_signer(
+        accounts.token_metadata_program.to_account_info(),
+        ThawDelegatedAccount {
+            metadata: accounts.edition_metadata.to_account_info(),
+            delegate: accounts.school.to_account_info(),
+            token_account: accounts.edition_token_account.to_account_info(),
+            edition: accounts.edition_account.to_account_info(),
+            mint: accounts.edition_mint.to_account_info(),
+            token_program: accounts.token_program.to_account_info(),
+        },
+        &[&seeds[..]],
+    ))?;
+
+    burn_edition_nft(CpiContext::new(
+        accounts.token_metadata_program.to_account_info(),
+        BurnEditionNft {
+            metadata: accounts.edition_metadata.to_account_info(),
+            owner: accounts.wallet.to_account_info(),
+            print_edition_mint: accounts.edition_mint.to_account_info(),
+            master_edition_mint: accounts.master_mint.to_account_info(),
+            print_edition_token: accounts.edition_token_account.to_account_info(),
+            master_edition_token: accounts.master_token_account.to_account_info(),
+            master_edition: accounts.master_edition.to_account_info(),
+            print_edition: accounts.edition_account.to_account_info(),
+            edition_marker: accounts.edition_marker.to_account_info(),
+            spl_token: accounts.token_program.to_account_info(),
+        },
+    ))?;
+    ctx.accounts.book.return_copy()
+}
+
+#[derive(Accounts)]
+pub struct ReclaimOverdueBook<'info> {
+    /// Pays for the school's token account the copy is moved into and gets that rent back.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub school: Box<Account<'info, School>>,
+    #[account(
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump = staff_registry.get_bump(),
+        constraint = staff_registry.authorizes(&school, &authority.key(), StaffRole::Librarian)
+            @ SchoolError::Unauthorized
+    )]
+    pub staff_registry: Box<Account<'info, StaffRegistry>>,
+    #[account(mut, constraint = book.get_school() == school.key() @ BookError::BookNotInSchool)]
+    pub book: Box<Account<'info, Book>>,
+    #[account(
+        mut,
+        close = wallet,
+        seeds = [b"book_loan", book.key().as_ref(), edition_mint.key().as_ref()],
+        bump = loan.get_bump()
+    )]
+    pub loan: Box<Account<'info, BookLoan>>,
+    /// The borrower, who gets back the rent of the loan record and of the burned edition.
+    #[account(mut, address = loan.get_wallet())]
+    pub wallet: SystemAccount<'info>,
+    #[account(address = book.get_nft_mint())]
+    pub master_mint: Box<Account<'info, Mint>>,
+    #[account(
+        associated_token::mint = master_mint,
+        associated_token::authority = school,
+        constraint = master_token_account.amount == 1
+    )]
+    pub master_token_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK: Address is derived using a known PDA
+    #[account(mut, address = MasterEdition::find_pda(&master_mint.key()).0)]
+    pub master_edition: AccountInfo<'info>,
+    #[account(mut)]
+    pub edition_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = edition_mint,
+        associated_token::authority = wallet
+    )]
+    pub edition_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = edition_mint,
+        associated_token::authority = school
+    )]
+    pub school_edition_token_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK: Address is derived using a known PDA
+    #[account(mut, address = MetaDataAccount::find_pda(&edition_mint.key()).0)]
+    pub edition_metadata: AccountInfo<'info>,
+    /// CHECK: Address is derived using a known PDA
+    #[account(mut, address = MasterEdition::find_pda(&edition_mint.key()).0)]
+    pub edition_account: AccountInfo<'info>,
+    /// CHECK: Address is derived using a known PDA
+    #[account(
+        mut,
+        address = EditionMarker::find_pda(
+            &master_mint.key(),
+            &(loan.get_edition() / EDITION_MARKER_BIT_SIZE).to_string()
+        ).0
+    )]
+    pub edition_marker: AccountInfo<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Takes an overdue edition back from the borrower and puts the copy back on the shelf.
+///
+/// The school thaws the edition and, as the delegate the student approved in `request_book`,
+/// moves it into its own token account, then burns it through Metaplex so that the edition
+/// number is freed and the book's available copies are restored. Burning closes the edition
+/// accounts into the school; that rent is passed on, the token account's share to the
+/// librarian who paid for it and the rest, with the loan record, to the borrower.
+pub fn reclaim_overdue_book(ctx: Context<ReclaimOverdueBook>) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let clock = Clock::get()?;
+    require!(
+        accounts.loan.is_overdue(clock.unix_timestamp),
+        BookError::NotOverdue
+    );
+
+    let seeds = accounts.school.signer_seeds();
+    let signer = &[&seeds[..]];
+    thaw_delegated_account(CpiContext::new_with_signer(
+        accounts.token_metadata_program.to_account_info(),
+        ThawDelegatedAccount {
+            metadata: accounts.edition_metadata.to_account_info(),
+            delegate: accounts.school.to_account_info(),
+            token_account: accounts.edition_token_account.to_account_info(),
+            edition: accounts.edition_account.to_account_info(),
+            mint: accounts.edition_mint.to_account_info(),
+            token_program: accounts.token_program.to_account_info(),
+        },
+        signer,
+    ))?;
+    transfer(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            Transfer {
+                from: accounts.edition_token_account.to_account_info(),
+                to: accounts.school_edition_token_account.to_account_info(),
+                authority: accounts.school.to_account_info(),
+            },
+            signer,
+        ),
+        1,
+    )?;
+
+    let school_lamports = accounts.school.get_lamports();
+    let token_account_rent = accounts.school_edition_token_account.get_lamports();
+    burn_edition_nft(CpiContext::new_with_signer(
+        accounts.token_metadata_program.to_account_info(),
+        BurnEditionNft {
+            metadata: accounts.edition_metadata.to_account_info(),
+            owner: accounts.school.to_account_info(),
+            print_edition_mint: accounts.edition_mint.to_account_info(),
+            master_edition_mint: accounts.master_mint.to_account_info(),
+            print_edition_token: accounts.school_edition_token_account.to_account_info(),
+            master_edition_token: accounts.master_token_account.to_account_info(),
+            master_edition: accounts.master_edition.to_account_info(),
+            print_edition: accounts.edition_account.to_account_info(),
+            edition_marker: accounts.edition_marker.to_account_info(),
+            spl_token: accounts.token_program.to_account_info(),
+        },
+        signer,
+    ))?;
+
+    let reclaimed = accounts
+        .school
+        .get_lamports()
+        .checked_sub(school_lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let librarian_share = reclaimed.min(token_account_rent);
+    accounts.school.sub_lamports(reclaimed)?;
+    accounts.authority.add_lamports(librarian_share)?;
+    accounts.wallet.add_lamports(reclaimed - librarian_share)?;
+    ctx.accounts.book.return_copy()
+}
@@ -14,8 +14,8 @@ use mpl_token_metadata::{
 
 use crate::{
     constants::{NftMetaDataAttributes, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH},
-    error::NFTError,
-    RateLimit,
+    error::{NFTError, SchoolError},
+    RateLimit, RateLimitPolicy, School, StaffRegistry, StaffRegistryTrait,
 };
 /// Defines the core functionality for creating and managing NFTs.
 ///
@@ -26,18 +26,17 @@ use crate::{
 pub trait NFTCreator<'info> {
     fn mint_nft_token(&mut self) -> Result<()>;
     fn create_nft_metadata(&self, name: &str, symbol: &str, uri: &str) -> Result<()>;
-    fn create_nft_master_edition(&self) -> Result<()>;
+    fn create_nft_master_edition(&self, max_supply: Option<u64>) -> Result<()>;
     fn create_meta_data_accounts(&self) -> CreateMetadataAccountsV3<'info>;
     fn create_master_edition_account(&self) -> CreateMasterEditionV3<'info>;
     fn enforce_rate_limit(&mut self) -> Result<()>;
 }
 
-impl<'info> NFTCreator<'info> for Context<'_, '_, '_, 'info, InitNFT<'info>> {
-    /// Enforces a rate limit on minting operations.
+impl<'info> NFTCreator<'info> for InitNFT<'info> {
+    /// Enforces the school's rate limit policy on minting operations.
     ///
-    /// This function implements a sliding window rate limit:
-    /// - Allows up to 5 mints per hour
-    /// - Resets the count if more than an hour has passed since the last mint
+    /// The window length, quota and algorithm come from the school's `RateLimitPolicy`,
+    /// which defaults to 5 mints per fixed one hour window until the school authority updates it.
     ///
     /// # Errors
     ///
@@ -45,36 +44,30 @@ impl<'info> NFTCreator<'info> for Context<'_, '_, '_, 'info, InitNFT<'info>> {
     /// May also return errors from clock operations.
     fn enforce_rate_limit(&mut self) -> Result<()> {
         let clock = Clock::get()?;
-        let current_time = clock.unix_timestamp;
-
-        if current_time - self.accounts.rate_limit.last_mint_time < 3600 {
-            if self.accounts.rate_limit.mint_count >= 5 {
-                return Err(NFTError::RateLimitExceeded.into());
-            }
-        } else {
-            self.accounts.rate_limit.mint_count = 0;
-        }
-        self.accounts.rate_limit.last_mint_time = current_time;
-        self.accounts.rate_limit.mint_count += 1;
-        Ok(())
+        self.rate_limit
+            .consume(&self.rate_limit_policy, clock.unix_timestamp)
     }
 
-    /// Mints a single NFT token to the associated token account.
+    /// Mints a single NFT token to the school's associated token account.
     ///
     /// This function creates a Cross-Program Invocation (CPI) context to interact with the Token program,
-    /// minting exactly one token to represent a non-fungible asset. The use of `mint_to` with a quantity
-    /// of 1 ensures the uniqueness of the NFT, adhering to the standard practice for NFT creation on Solana.
+    /// signed by the school PDA as mint authority, minting exactly one token to represent a non-fungible
+    /// asset. The use of `mint_to` with a quantity of 1 ensures the uniqueness of the NFT, adhering to the
+    /// standard practice for NFT creation on Solana.
     /// Note: This function assumes that the mint account is properly initialized for an NFT
     /// (i.e., with decimals set to 0 and a supply limit of 1).
     fn mint_nft_token(&mut self) -> Result<()> {
         self.enforce_rate_limit()?;
-        let cpi_context = CpiContext::new(
-            self.accounts.token_program.to_account_info(),
+        let seeds = self.school.signer_seeds();
+        let signer = &[&seeds[..]];
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
             MintTo {
-                mint: self.accounts.mint.to_account_info(),
-                to: self.accounts.associated_token_account.to_account_info(),
-                authority: self.accounts.authority.to_account_info(),
+                mint: self.mint.to_account_info(),
+                to: self.associated_token_account.to_account_info(),
+                authority: self.school.to_account_info(),
             },
+            signer,
         );
         mint_to(cpi_context, 1)?;
         Ok(())
@@ -83,19 +76,19 @@ impl<'info> NFTCreator<'info> for Context<'_, '_, '_, 'info, InitNFT<'info>> {
     /// Constructs a CreateMetadataAccountsV3 struct for metadata account creation.
     ///
     /// This function prepares the necessary account information for creating
-    /// metadata associated with an NFT or token. It uses the authority account
-    /// as the signer for mint authority, update authority, and payer roles,
-    /// simplifying the account structure for metadata creation operations.
+    /// metadata associated with an NFT or token. The school PDA is the mint and
+    /// update authority, so the NFT stays under the school's control whoever minted it,
+    /// while the authority account pays for the metadata.
     fn create_meta_data_accounts(&self) -> CreateMetadataAccountsV3<'info> {
-        let signer = &self.accounts.authority;
+        let school = &self.school;
         CreateMetadataAccountsV3 {
-            metadata: self.accounts.metadata_account.to_account_info(),
-            mint: self.accounts.mint.to_account_info(),
-            mint_authority: signer.to_account_info(),
-            update_authority: signer.to_account_info(),
-            payer: signer.to_account_info(),
-            system_program: self.accounts.system_program.to_account_info(),
-            rent: self.accounts.rent.to_account_info(),
+            metadata: self.metadata_account.to_account_info(),
+            mint: self.mint.to_account_info(),
+            mint_authority: school.to_account_info(),
+            update_authority: school.to_account_info(),
+            payer: self.authority.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            rent: self.rent.to_account_info(),
         }
     }
 
@@ -106,6 +99,7 @@ impl<'info> NFTCreator<'info> for Context<'_, '_, '_, 'info, InitNFT<'info>> {
     /// 2. Sets up the necessary accounts for metadata creation
     /// 3. Constructs the metadata using DataV2 structure
     /// 4. Calls the external 'create_metadata_accounts_v3' function to finalize the process
+    ///
     /// Note: This function sets seller fees to 0 and does not include creators, collection, or uses data.
     fn create_nft_metadata(&self, name: &str, symbol: &str, uri: &str) -> Result<()> {
         let nft_props = NftMetaDataAttributes {
@@ -113,13 +107,14 @@ impl<'info> NFTCreator<'info> for Context<'_, '_, '_, 'info, InitNFT<'info>> {
             uri: uri.to_string(),
             symbol: symbol.to_string(),
         };
-        if let Err(error) = validate_nft_meta_data_attributes(nft_props) {
-            return Err(error);
-        }
+        validate_nft_meta_data_attributes(nft_props)?;
         let accounts = self.create_meta_data_accounts();
-        let cpi_context = CpiContext::new(
-            self.accounts.token_metadata_program.to_account_info(),
+        let seeds = self.school.signer_seeds();
+        let signer = &[&seeds[..]];
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_metadata_program.to_account_info(),
             accounts,
+            signer,
         );
 
         let data = DataV2 {
@@ -139,35 +134,38 @@ impl<'info> NFTCreator<'info> for Context<'_, '_, '_, 'info, InitNFT<'info>> {
     ///
     /// This function sets up the necessary accounts and authorities for creating
     /// a master edition, which is typically used for limited edition NFTs.
-    /// The 'authority' account serves as the signer, update authority, and mint authority.
+    /// The school PDA serves as update authority and mint authority, and the 'authority' account pays.
     fn create_master_edition_account(&self) -> CreateMasterEditionV3<'info> {
-        let signer = &self.accounts.authority;
+        let school = &self.school;
         CreateMasterEditionV3 {
-            edition: self.accounts.master_edition_account.to_account_info(),
-            mint: self.accounts.mint.to_account_info(),
-            update_authority: signer.to_account_info(),
-            mint_authority: signer.to_account_info(),
-            payer: signer.to_account_info(),
-            metadata: self.accounts.metadata_account.to_account_info(),
-            token_program: self.accounts.token_program.to_account_info(),
-            system_program: self.accounts.system_program.to_account_info(),
-            rent: self.accounts.rent.to_account_info(),
+            edition: self.master_edition_account.to_account_info(),
+            mint: self.mint.to_account_info(),
+            update_authority: school.to_account_info(),
+            mint_authority: school.to_account_info(),
+            payer: self.authority.to_account_info(),
+            metadata: self.metadata_account.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            rent: self.rent.to_account_info(),
         }
     }
 
     /// Creates a Master Edition NFT using the Token Metadata Program.
     ///
     /// This function sets up the necessary accounts and invokes the
-    /// `create_master_edition_v3` CPI (Cross-Program Invocation) with no
-    /// max supply limit. Master Editions serve as templates for printing
-    /// Edition NFTs and are crucial for creating collections or series of NFTs
-    fn create_nft_master_edition(&self) -> Result<()> {
+    /// `create_master_edition_v3` CPI (Cross-Program Invocation) with the given
+    /// max supply, where `None` leaves the number of prints unlimited. Master Editions serve
+    /// as templates for printing Edition NFTs and are crucial for creating collections or series of NFTs
+    fn create_nft_master_edition(&self, max_supply: Option<u64>) -> Result<()> {
         let accounts = self.create_master_edition_account();
-        let cpi_context = CpiContext::new(
-            self.accounts.token_metadata_program.to_account_info(),
+        let seeds = self.school.signer_seeds();
+        let signer = &[&seeds[..]];
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_metadata_program.to_account_info(),
             accounts,
+            signer,
         );
-        create_master_edition_v3(cpi_context, None)?;
+        create_master_edition_v3(cpi_context, max_supply)?;
         Ok(())
     }
 }
@@ -182,26 +180,30 @@ impl<'info> NFTCreator<'info> for Context<'_, '_, '_, 'info, InitNFT<'info>> {
 ///
 /// Key aspects:
 /// - Uses PDAs for metadata and master edition accounts
-/// - Initializes mint with 0 decimals and sets authority
-/// - Creates or uses existing associated token account
+/// - Initializes mint with 0 decimals and the school PDA as mint and freeze authority
+/// - Holds the token in the school PDA's associated token account, so the NFT stays with
+///   the school when staff change or the school authority is transferred
+/// - Only lets the school authority or its staff mint against the school's rate limit policy
+/// - Creates the school's rate limit policy and the authority's rate limit PDA on first use
 /// - Integrates with various Solana programs (Token, Metadata, System)
 #[derive(Accounts)]
 pub struct InitNFT<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
+    pub school: Account<'info, School>,
     #[account(
         init,
         payer = authority,
         mint::decimals = 0,
-        mint::authority = authority.key(),
-        mint::freeze_authority = authority.key()
+        mint::authority = school.key(),
+        mint::freeze_authority = school.key()
     )]
     pub mint: Account<'info, Mint>,
     #[account(
         init_if_needed,
         payer = authority,
         associated_token::mint = mint,
-        associated_token::authority = authority
+        associated_token::authority = school
     )]
     pub associated_token_account: Account<'info, TokenAccount>,
     /// CHECK: Address is derived using a known PDA
@@ -215,7 +217,25 @@ pub struct InitNFT<'info> {
     pub token_metadata_program: Program<'info, Metadata>,
     pub system_program: Program<'info, System>,
     #[account(
-        mut, seeds= [b"rate_limit", authority.key().as_ref()], bump
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump = staff_registry.get_bump(),
+        constraint = staff_registry.is_staff(&school, &authority.key()) @ SchoolError::Unauthorized
+    )]
+    pub staff_registry: Account<'info, StaffRegistry>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = RateLimitPolicy::LEN,
+        seeds = [b"rate_limit_policy", school.key().as_ref()],
+        bump
+    )]
+    pub rate_limit_policy: Account<'info, RateLimitPolicy>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = RateLimit::LEN,
+        seeds = [b"rate_limit", school.key().as_ref(), authority.key().as_ref()],
+        bump
     )]
     pub rate_limit: Account<'info, RateLimit>,
     pub rent: Sysvar<'info, Rent>,
@@ -226,21 +246,54 @@ pub struct InitNFT<'info> {
 /// This function checks that:
 /// - None of the attributes (name, symbol, uri) are empty
 /// - The name length is within the acceptable limit (32 characters)
-/// - The symbol length is within the acceptable limit (3 characters)
+/// - The symbol length is within the acceptable limit (10 characters)
 /// - The URI starts with "https" for secure access
 ///
 /// If any validation fails, it returns a corresponding NFTError.
 /// This helps maintain consistency and security in NFT metadata across the system.
-fn validate_nft_meta_data_attributes(props: NftMetaDataAttributes) -> Result<()> {
+pub(crate) fn validate_nft_meta_data_attributes(props: NftMetaDataAttributes) -> Result<()> {
     let NftMetaDataAttributes { name, symbol, uri } = props;
     require!(!name.is_empty(), NFTError::EmptyAttribute);
     require!(!symbol.is_empty(), NFTError::EmptyAttribute);
     require!(!uri.is_empty(), NFTError::EmptyAttribute);
     require!(name.len() <= MAX_NAME_LENGTH, NFTError::InvalidNameLength);
     require!(
-        !symbol.len() <= MAX_SYMBOL_LENGTH,
+        symbol.len() <= MAX_SYMBOL_LENGTH,
         NFTError::InvalidSymbolLength
     );
-    require!(!uri.starts_with("https"), NFTError::InvalidURI);
+    require!(uri.starts_with("https"), NFTError::InvalidURI);
     Ok(())
 }
+
+impl<'info> InitNFT<'info> {
+    /// Mints a new NFT into the school's custody and attaches its metadata and master edition.
+    ///
+    /// The school's rate limit policy is filled with defaults the first time it is used.
+    /// The token is minted first so that the master edition CPI finds a supply of exactly one.
+    /// All three steps run in the same instruction, so a failure in any of them reverts the mint.
+    /// `max_supply` caps how many editions can be printed from the master edition.
+    pub fn mint_with_metadata(
+        &mut self,
+        bumps: &InitNFTBumps,
+        name: &str,
+        symbol: &str,
+        uri: &str,
+        max_supply: Option<u64>,
+    ) -> Result<()> {
+        if !self.rate_limit_policy.is_initialized() {
+            let school = self.school.key();
+            self.rate_limit_policy
+                .set_defaults(school, bumps.rate_limit_policy);
+        }
+        self.rate_limit.bump = bumps.rate_limit;
+        self.mint_nft_token()?;
+        self.create_nft_metadata(name, symbol, uri)?;
+        self.create_nft_master_edition(max_supply)?;
+        Ok(())
+    }
+}
+
+pub fn mint_nft(ctx: Context<InitNFT>, name: String, symbol: String, uri: String) -> Result<()> {
+    ctx.accounts
+        .mint_with_metadata(&ctx.bumps, &name, &symbol, &uri, None)
+}
@@ -1,5 +1,35 @@
+pub mod book;
+pub mod completion;
+pub mod course;
+pub mod fee_schedule;
+pub mod lending;
 pub mod mintnft;
+pub mod multisig;
+pub mod payment_plan;
+pub mod ratelimit;
+pub mod registration;
+pub mod scholarship;
 pub mod school;
+pub mod staff;
+pub mod student;
+pub mod treasury;
+pub mod tuition;
+pub mod waitlist;
 
+pub use book::*;
+pub use completion::*;
+pub use course::*;
+pub use fee_schedule::*;
+pub use lending::*;
 pub use mintnft::*;
+pub use multisig::*;
+pub use payment_plan::*;
+pub use ratelimit::*;
+pub use registration::*;
+pub use scholarship::*;
 pub use school::*;
+pub use staff::*;
+pub use student::*;
+pub use treasury::*;
+pub use tuition::*;
+pub use waitlist::*;
@@ -0,0 +1,420 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+use super::{
+    ratelimit::apply_rate_limit_policy,
+    scholarship::{create_scholarship, validate_scholarship},
+    school::{
+        apply_cancel_authority_transfer, apply_enrollment_fee, apply_fee_multiplier,
+        apply_pending_authority, apply_school_name,
+    },
+    staff::{apply_grant_role, apply_revoke_role},
+    treasury::{apply_payment_mint, withdraw, withdraw_tokens},
+};
+use crate::{
+    constants::MAX_MULTISIG_SIGNERS,
+    error::{MultisigError, SchoolError},
+    events::{MultisigCreated, ProposalApproved, ProposalCreated, ProposalExecuted},
+    Course, FeeSchedule, FeeScheduleTrait, Multisig, MultisigTrait, Proposal, ProposalAction,
+    ProposalTrait, RateLimitPolicy, School, SchoolTrait, StaffRegistry, StaffRegistryTrait,
+    Treasury, TreasuryTrait,
+};
+
+#[derive(Accounts)]
+#[instruction(signers: Vec<Pubkey>)]
+pub struct CreateMultisig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        constraint = school.get_authority() == authority.key() @ SchoolError::Unauthorized
+    )]
+    pub school: Account<'info, School>,
+    #[account(
+        init,
+        payer = authority,
+        space = Multisig::space(signers.len()),
+        seeds = [b"multisig", school.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Hands the school authority over to an M-of-N signer set.
+///
+/// From then on every change that needs the authority goes through a proposal with
+/// `threshold` approvals, and instructions that require the authority's signature directly
+/// are no longer available.
+pub fn create_multisig(
+    ctx: Context<CreateMultisig>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    require!(
+        !signers.is_empty() && signers.len() <= MAX_MULTISIG_SIGNERS as usize,
+        MultisigError::InvalidSigners
+    );
+    require!(
+        signers
+            .iter()
+            .enumerate()
+            .all(|(i, signer)| !signers[..i].contains(signer)),
+        MultisigError::InvalidSigners
+    );
+    require!(
+        threshold > 0 && threshold as usize <= signers.len(),
+        MultisigError::InvalidThreshold
+    );
+    let school = &mut ctx.accounts.school;
+    let multisig = &mut ctx.accounts.multisig;
+    multisig.set_inner(Multisig::new(
+        school.key(),
+        signers.clone(),
+        threshold,
+        ctx.bumps.multisig,
+    ));
+    school.set_authority(multisig.key());
+    // A transfer proposed by the previous key must not bypass the signer set.
+    school.set_pending_authority(None);
+    emit!(MultisigCreated {
+        school: school.key(),
+        multisig: multisig.key(),
+        signers,
+        threshold,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub school: Account<'info, School>,
+    #[account(
+        mut,
+        seeds = [b"multisig", school.key().as_ref()],
+        bump = multisig.get_bump(),
+        constraint = school.get_authority() == multisig.key() @ SchoolError::Unauthorized,
+        constraint = multisig.is_signer(&proposer.key()) @ MultisigError::NotASigner
+    )]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::space(multisig.get_signers().len()),
+        seeds = [
+            b"proposal",
+            multisig.key().as_ref(),
+            &multisig.get_proposal_count().to_le_bytes()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Opens a proposal for `action`, counting the proposer's approval.
+pub fn create_proposal(ctx: Context<CreateProposal>, action: ProposalAction) -> Result<()> {
+    action.validate()?;
+    let multisig = &mut ctx.accounts.multisig;
+    let proposal = &mut ctx.accounts.proposal;
+    let index = multisig.get_proposal_count();
+    proposal.set_inner(Proposal::new(
+        multisig.key(),
+        index,
+        ctx.accounts.proposer.key(),
+        action.clone(),
+        ctx.bumps.proposal,
+    ));
+    multisig.increment_proposal_count()?;
+    emit!(ProposalCreated {
+        multisig: multisig.key(),
+        proposal: proposal.key(),
+        proposer: ctx.accounts.proposer.key(),
+        action,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    pub signer: Signer<'info>,
+    #[account(constraint = multisig.is_signer(&signer.key()) @ MultisigError::NotASigner)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            multisig.key().as_ref(),
+            &proposal.get_index().to_le_bytes()
+        ],
+        bump = proposal.get_bump()
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+    let signer = ctx.accounts.signer.key();
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.approve(signer)?;
+    emit!(ProposalApproved {
+        multisig: ctx.accounts.multisig.key(),
+        proposal: proposal.key(),
+        signer,
+        approvals: proposal.get_approvals().len() as u8,
+    });
+    Ok(())
+}
+
+/// Accounts for executing a proposal.
+///
+/// The optional accounts are only needed by the actions that touch them.
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    /// Pays for accounts an action creates or grows, and gets back rent an action frees.
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    #[account(mut)]
+    pub school: Box<Account<'info, School>>,
+    #[account(
+        seeds = [b"multisig", school.key().as_ref()],
+        bump = multisig.get_bump(),
+        constraint = school.get_authority() == multisig.key() @ SchoolError::Unauthorized,
+        constraint = multisig.is_signer(&executor.key()) @ MultisigError::NotASigner
+    )]
+    pub multisig: Box<Account<'info, Multisig>>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [
+            b"proposal",
+            multisig.key().as_ref(),
+            &proposal.get_index().to_le_bytes()
+        ],
+        bump = proposal.get_bump()
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+    /// Receives the rent of the executed proposal.
+    #[account(mut, address = proposal.get_proposer())]
+    pub proposer: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"treasury", school.key().as_ref()],
+        bump = treasury.get_bump()
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+    /// CHECK: Only used by treasury withdrawals, checked against the proposal.
+    #[account(mut)]
+    pub recipient: Option<UncheckedAccount<'info>>,
+    #[account(seeds = [b"fee_schedule"], bump = fee_schedule.get_bump())]
+    pub fee_schedule: Box<Account<'info, FeeSchedule>>,
+    #[account(
+        mut,
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump = staff_registry.get_bump()
+    )]
+    pub staff_registry: Option<Box<Account<'info, StaffRegistry>>>,
+    #[account(
+        init_if_needed,
+        payer = executor,
+        space = RateLimitPolicy::LEN,
+        seeds = [b"rate_limit_policy", school.key().as_ref()],
+        bump
+    )]
+    pub rate_limit_policy: Option<Box<Account<'info, RateLimitPolicy>>>,
+    pub payment_mint: Option<Box<Account<'info, Mint>>>,
+    #[account(
+        init_if_needed,
+        payer = executor,
+        associated_token::mint = payment_mint,
+        associated_token::authority = treasury
+    )]
+    pub treasury_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    /// Only used by treasury token withdrawals, checked against the proposal.
+    #[account(mut)]
+    pub recipient_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    /// CHECK: The scholarship an `IssueScholarship` action creates, checked against the
+    /// PDA derived from the proposal.
+    #[account(mut)]
+    pub scholarship: Option<UncheckedAccount<'info>>,
+    /// The course an `IssueScholarship` action is scoped to, if any.
+    pub course: Option<Box<Account<'info, Course>>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Resizes the school account to `len` bytes, settling the rent difference with `payer`
+/// the same way a `realloc` constraint does.
+fn resize_school<'info>(
+    school: &Account<'info, School>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    len: usize,
+) -> Result<()> {
+    let info = school.to_account_info();
+    let rent = Rent::get()?.minimum_balance(len);
+    let lamports = info.lamports();
+    if rent > lamports {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            rent - lamports,
+        )?;
+    } else if len < info.data_len() {
+        info.sub_lamports(lamports - rent)?;
+        payer.add_lamports(lamports - rent)?;
+    }
+    info.realloc(len, false)?;
+    Ok(())
+}
+
+/// Applies an approved proposal to the school and closes it.
+pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    let proposal = &accounts.proposal;
+    require!(
+        proposal.get_approvals().len() >= accounts.multisig.get_threshold() as usize,
+        MultisigError::NotEnoughApprovals
+    );
+    let school = &mut accounts.school;
+    match proposal.get_action().clone() {
+        ProposalAction::UpdateEnrollmentFee { new_fee } => apply_enrollment_fee(school, new_fee)?,
+        ProposalAction::UpdateFeeMultiplier { new_multiplier } => {
+            apply_fee_multiplier(school, &accounts.fee_schedule, new_multiplier)?
+        }
+        ProposalAction::TransferAuthority { new_authority } => {
+            apply_pending_authority(school, new_authority)?
+        }
+        ProposalAction::WithdrawTreasury { amount, recipient } => {
+            let recipient_account = accounts
+                .recipient
+                .as_ref()
+                .filter(|account| account.key() == recipient)
+                .ok_or(MultisigError::InvalidRecipient)?;
+            withdraw(
+                &mut accounts.treasury,
+                &recipient_account.to_account_info(),
+                amount,
+            )?
+        }
+        ProposalAction::CancelAuthorityTransfer => apply_cancel_authority_transfer(school)?,
+        ProposalAction::UpdateSchoolName { name } => {
+            resize_school(
+                school,
+                &accounts.executor,
+                &accounts.system_program,
+                School::space(name.len()),
+            )?;
+            apply_school_name(school, name)?
+        }
+        ProposalAction::GrantStaffRole { member, role } => {
+            let staff_registry = accounts
+                .staff_registry
+                .as_mut()
+                .ok_or(MultisigError::ProposalAccountMismatch)?;
+            apply_grant_role(staff_registry, member, role)?
+        }
+        ProposalAction::RevokeStaffRole { member, role } => {
+            let staff_registry = accounts
+                .staff_registry
+                .as_mut()
+                .ok_or(MultisigError::ProposalAccountMismatch)?;
+            apply_revoke_role(staff_registry, member, role)?
+        }
+        ProposalAction::UpdateRateLimitPolicy {
+            mode,
+            period,
+            max_mints,
+        } => {
+            let policy = accounts
+                .rate_limit_policy
+                .as_mut()
+                .ok_or(MultisigError::ProposalAccountMismatch)?;
+            let bump = ctx
+                .bumps
+                .rate_limit_policy
+                .ok_or(MultisigError::ProposalAccountMismatch)?;
+            apply_rate_limit_policy(school.key(), policy, bump, mode, period, max_mints)?
+        }
+        ProposalAction::UpdatePaymentMint { payment_mint } => {
+            require!(
+                accounts.payment_mint.as_ref().map(|mint| mint.key()) == payment_mint,
+                MultisigError::ProposalAccountMismatch
+            );
+            apply_payment_mint(
+                school,
+                &mut accounts.treasury,
+                payment_mint,
+                accounts.treasury_token_account.is_some(),
+            )?
+        }
+        ProposalAction::WithdrawTreasuryTokens { amount, recipient } => {
+            let (Some(payment_mint), Some(treasury_token_account)) = (
+                accounts.payment_mint.as_ref(),
+                accounts.treasury_token_account.as_ref(),
+            ) else {
+                return err!(MultisigError::ProposalAccountMismatch);
+            };
+            let recipient_account = accounts
+                .recipient_token_account
+                .as_ref()
+                .filter(|account| account.key() == recipient && account.mint == payment_mint.key())
+                .ok_or(MultisigError::InvalidRecipient)?;
+            withdraw_tokens(
+                school,
+                &mut accounts.treasury,
+                payment_mint,
+                treasury_token_account,
+                recipient_account,
+                &accounts.token_program,
+                amount,
+            )?
+        }
+        ProposalAction::IssueScholarship {
+            wallet,
+            course,
+            discount,
+            expires_at,
+        } => {
+            validate_scholarship(
+                school.key(),
+                course,
+                accounts.course.as_deref(),
+                &discount,
+                expires_at,
+            )?;
+            let scholarship = accounts
+                .scholarship
+                .as_ref()
+                .ok_or(MultisigError::ProposalAccountMismatch)?;
+            create_scholarship(
+                school,
+                scholarship,
+                &accounts.executor,
+                &accounts.system_program,
+                wallet,
+                course,
+                discount,
+                expires_at,
+            )?
+        }
+    }
+    emit!(ProposalExecuted {
+        multisig: accounts.multisig.key(),
+        proposal: proposal.key(),
+    });
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+
+use super::{student::*, treasury::*};
+use crate::{
+    constants::{INSTALLMENT_GRACE_PERIOD, INSTALLMENT_INTERVAL, MAX_INSTALLMENTS},
+    error::StudentError,
+    PaymentPlan, PaymentPlanTrait, School, SchoolTrait, Student, StudentTrait, Treasury,
+    TreasuryTrait,
+};
+
+/// Fails when the student has a payment plan with an installment overdue past the grace period.
+pub(crate) fn require_good_standing(
+    student: &Student,
+    payment_plan: Option<&PaymentPlan>,
+    now: i64,
+) -> Result<()> {
+    if !student.has_payment_plan() {
+        return Ok(());
+    }
+    let payment_plan = payment_plan.ok_or(StudentError::PaymentPlanRequired)?;
+    require!(
+        !payment_plan.is_delinquent(now, INSTALLMENT_GRACE_PERIOD),
+        StudentError::Suspended
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct EnrollWithPaymentPlan<'info> {
+    pub enroll: Enroll<'info>,
+    #[account(
+        init,
+        payer = enroll.wallet,
+        space = PaymentPlan::LEN,
+        seeds = [b"payment_plan", enroll.student.key().as_ref()],
+        bump
+    )]
+    pub payment_plan: Box<Account<'info, PaymentPlan>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Enrolls the signing wallet and splits its enrollment fee into `installment_count`
+/// installments, the first of which is paid now and the rest every `INSTALLMENT_INTERVAL`.
+///
+/// Missing an installment by more than `INSTALLMENT_GRACE_PERIOD` suspends the student from
+/// registering for courses until it is paid.
+pub fn enroll_with_payment_plan(
+    ctx: Context<EnrollWithPaymentPlan>,
+    uri: String,
+    installment_count: u8,
+) -> Result<()> {
+    require!(
+        installment_count > 1 && installment_count <= MAX_INSTALLMENTS,
+        StudentError::InvalidInstallmentCount
+    );
+    let fee = ctx.accounts.enroll.enrollment_fee()?;
+    let payment_plan = &mut ctx.accounts.payment_plan;
+    payment_plan.set_inner(PaymentPlan::new(
+        ctx.accounts.enroll.student.key(),
+        fee,
+        installment_count,
+        Clock::get()?.unix_timestamp,
+        INSTALLMENT_INTERVAL,
+        ctx.accounts.enroll.school.get_payment_mint(),
+        ctx.bumps.payment_plan,
+    ));
+    let first_installment = payment_plan.record_payment()?;
+    ctx.accounts
+        .enroll
+        .admit(&ctx.bumps.enroll, &uri, first_installment)?;
+    ctx.accounts.enroll.student.set_has_payment_plan(true);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PayInstallment<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    pub school: Account<'info, School>,
+    #[account(
+        mut,
+        seeds = [b"student", school.key().as_ref(), wallet.key().as_ref()],
+        bump = student.get_bump()
+    )]
+    pub student: Account<'info, Student>,
+    #[account(
+        mut,
+        seeds = [b"payment_plan", student.key().as_ref()],
+        bump = payment_plan.get_bump()
+    )]
+    pub payment_plan: Account<'info, PaymentPlan>,
+    #[account(
+        mut,
+        seeds = [b"treasury", school.key().as_ref()],
+        bump = treasury.get_bump()
+    )]
+    pub treasury: Account<'info, Treasury>,
+    pub payment: FeePayment<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Pays the student's next installment, lifting a suspension once no installment is overdue.
+///
+/// Installments are paid in the currency the plan was opened in, so a later
+/// `update_payment_mint` does not change what the student owes.
+pub fn pay_installment(ctx: Context<PayInstallment>) -> Result<()> {
+    let amount = ctx.accounts.payment_plan.record_payment()?;
+    let student = &mut ctx.accounts.student;
+    let fee_paid = student
+        .get_fee_paid()
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    student.set_fee_paid(fee_paid);
+    ctx.accounts.payment.collect_in(
+        ctx.accounts.payment_plan.get_payment_mint(),
+        &ctx.accounts.school,
+        &mut ctx.accounts.treasury,
+        &ctx.accounts.wallet,
+        &ctx.accounts.system_program,
+        amount,
+    )
+}
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::RateLimitMode,
+    error::{NFTError, SchoolError},
+    RateLimit, RateLimitPolicy, School, SchoolTrait,
+};
+
+#[derive(Accounts)]
+pub struct UpdateRateLimitPolicy<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(constraint = school.get_authority() == authority.key() @ SchoolError::Unauthorized)]
+    pub school: Account<'info, School>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = RateLimitPolicy::LEN,
+        seeds = [b"rate_limit_policy", school.key().as_ref()],
+        bump
+    )]
+    pub rate_limit_policy: Account<'info, RateLimitPolicy>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Sets the minting window, quota and algorithm used for every minter of the school.
+pub fn update_rate_limit_policy(
+    ctx: Context<UpdateRateLimitPolicy>,
+    mode: RateLimitMode,
+    period: i64,
+    max_mints: u64,
+) -> Result<()> {
+    apply_rate_limit_policy(
+        ctx.accounts.school.key(),
+        &mut ctx.accounts.rate_limit_policy,
+        ctx.bumps.rate_limit_policy,
+        mode,
+        period,
+        max_mints,
+    )
+}
+
+/// Updates `policy`, filling in its defaults first if it was only just created.
+pub(crate) fn apply_rate_limit_policy(
+    school: Pubkey,
+    policy: &mut RateLimitPolicy,
+    bump: u8,
+    mode: RateLimitMode,
+    period: i64,
+    max_mints: u64,
+) -> Result<()> {
+    if !policy.is_initialized() {
+        policy.set_defaults(school, bump);
+    }
+    policy.update(mode, period, max_mints)
+}
+
+#[derive(Accounts)]
+pub struct CloseRateLimit<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub school: Account<'info, School>,
+    #[account(seeds = [b"rate_limit_policy", school.key().as_ref()], bump = rate_limit_policy.bump)]
+    pub rate_limit_policy: Account<'info, RateLimitPolicy>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"rate_limit", school.key().as_ref(), authority.key().as_ref()],
+        bump = rate_limit.bump
+    )]
+    pub rate_limit: Account<'info, RateLimit>,
+}
+
+/// Closes the authority's rate limit account and returns its rent.
+///
+/// Closing is only allowed once a full policy period has passed since the last mint,
+/// otherwise an authority could close and recreate the account to reset its quota.
+pub fn close_rate_limit(ctx: Context<CloseRateLimit>) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(
+        current_time - ctx.accounts.rate_limit.last_mint_time
+            >= ctx.accounts.rate_limit_policy.period,
+        NFTError::RateLimitWindowActive
+    );
+    Ok(())
+}
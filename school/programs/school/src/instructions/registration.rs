@@ -0,0 +1,200 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::associated_token::AssociatedToken;
+
+use super::{
+    completion::require_prerequisites, payment_plan::require_good_standing,
+    scholarship::apply_scholarship, treasury::*, tuition::*,
+};
+use crate::{
+    error::{CourseError, ScholarshipError, StudentError},
+    Course, CourseEnrollment, CourseEnrollmentTrait, CourseTrait, PaymentPlan, PaymentPlanTrait,
+    Scholarship, ScholarshipTrait, School, SchoolTrait, Student, StudentTrait, Treasury,
+    TreasuryTrait,
+};
+
+#[derive(Accounts)]
+pub struct RegisterForClass<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    pub school: Account<'info, School>,
+    #[account(
+        seeds = [b"student", school.key().as_ref(), wallet.key().as_ref()],
+        bump = student.get_bump(),
+        constraint = student.has_student_id() @ StudentError::StudentIdNotIssued
+    )]
+    pub student: Account<'info, Student>,
+    #[account(
+        constraint = payment_plan.get_student() == student.key() @ StudentError::PaymentPlanRequired
+    )]
+    pub payment_plan: Option<Account<'info, PaymentPlan>>,
+    #[account(
+        mut,
+        constraint = course.get_school() == school.key() @ CourseError::CourseNotInSchool
+    )]
+    pub course: Account<'info, Course>,
+    #[account(
+        init,
+        payer = wallet,
+        space = CourseEnrollment::LEN,
+        seeds = [b"course_enrollment", course.key().as_ref(), student.key().as_ref()],
+        bump
+    )]
+    pub enrollment: Account<'info, CourseEnrollment>,
+    /// Tuition is escrowed rather than collected, so `payment.treasury_token_account` is unused.
+    pub payment: FeePayment<'info>,
+    /// CHECK: The enrollment's associated token account for the payment mint, created here
+    /// when the school is paid in an SPL token.
+    #[account(mut)]
+    pub escrow_token_account: Option<UncheckedAccount<'info>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    #[account(
+        mut,
+        constraint = scholarship.covers(school.key(), wallet.key(), Some(course.key()))
+            @ ScholarshipError::NotApplicable
+    )]
+    pub scholarship: Option<Account<'info, Scholarship>>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RegisterForClass<'info> {
+    /// Moves `amount` from the wallet into the enrollment's escrow and returns the mint it is
+    /// held in, or `None` for lamports.
+    fn escrow_tuition(&self, amount: u64) -> Result<Option<Pubkey>> {
+        if amount == 0 {
+            return Ok(None);
+        }
+        let Some(payment_mint) = self.school.get_payment_mint() else {
+            transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.wallet.to_account_info(),
+                        to: self.enrollment.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+            return Ok(None);
+        };
+        self.payment.escrow_tokens(
+            payment_mint,
+            &self.wallet,
+            self.enrollment.to_account_info(),
+            self.escrow_token_account.as_ref(),
+            self.associated_token_program.as_ref(),
+            &self.system_program,
+            amount,
+        )?;
+        Ok(Some(payment_mint))
+    }
+}
+
+/// Registers an enrolled student for a course and escrows the course tuition.
+///
+/// Each (course, student) pair gets its own enrollment record PDA, so registering twice
+/// fails, and registration is refused with `CourseError::CourseFull` once the course
+/// has reached its capacity or while students are queued on its waitlist. The tuition is
+/// held by the enrollment record until `release_tuition` or `drop_class` settles it.
+///
+/// When the course has prerequisites, the student's completion record for each of them is
+/// passed, in order, as remaining accounts.
+pub fn register_for_class<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RegisterForClass<'info>>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    require_good_standing(
+        &ctx.accounts.student,
+        ctx.accounts.payment_plan.as_deref(),
+        clock.unix_timestamp,
+    )?;
+    let course = &ctx.accounts.course;
+    require!(!course.is_cancelled(), CourseError::CourseCancelled);
+    require!(!course.is_full(), CourseError::CourseFull);
+    require!(
+        course.get_waitlist_count() == 0,
+        CourseError::WaitlistPending
+    );
+    require_prerequisites(course, ctx.accounts.student.key(), ctx.remaining_accounts)?;
+
+    let tuition_fee =
+        apply_scholarship(ctx.accounts.scholarship.as_mut(), course.get_tution_fee())?;
+    let escrow_mint = ctx.accounts.escrow_tuition(tuition_fee)?;
+    let course = &mut ctx.accounts.course;
+    course.increment_enrolled_students_count()?;
+
+    ctx.accounts.enrollment.set_inner(CourseEnrollment::new(
+        course.key(),
+        ctx.accounts.student.key(),
+        tuition_fee,
+        clock.unix_timestamp,
+        escrow_mint,
+        course.get_add_drop_deadline(),
+        course.get_drop_refund_bps(),
+        ctx.bumps.enrollment,
+    ));
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DropClass<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    pub school: Account<'info, School>,
+    #[account(
+        seeds = [b"student", school.key().as_ref(), wallet.key().as_ref()],
+        bump = student.get_bump()
+    )]
+    pub student: Account<'info, Student>,
+    #[account(
+        mut,
+        constraint = course.get_school() == school.key() @ CourseError::CourseNotInSchool,
+        constraint = !course.is_cancelled() @ CourseError::CourseCancelled
+    )]
+    pub course: Account<'info, Course>,
+    #[account(
+        mut,
+        close = wallet,
+        seeds = [b"course_enrollment", course.key().as_ref(), student.key().as_ref()],
+        bump = enrollment.get_bump()
+    )]
+    pub enrollment: Account<'info, CourseEnrollment>,
+    /// CHECK: Only checked to be empty, so graded students cannot drop for a refund and
+    /// keep the credit.
+    #[account(
+        seeds = [b"completion", course.key().as_ref(), student.key().as_ref()],
+        bump,
+        constraint = completion_record.data_is_empty() @ CourseError::AlreadyCompleted
+    )]
+    pub completion_record: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"treasury", school.key().as_ref()],
+        bump = treasury.get_bump()
+    )]
+    pub treasury: Account<'info, Treasury>,
+    pub escrow: TuitionEscrow<'info>,
+}
+
+/// Drops the student from a course, freeing a seat for the waitlist. Students who have
+/// already been graded in the course cannot drop it.
+///
+/// Tuition still in escrow is refunded at the drop refund rate in force when the student
+/// registered, before that policy's add/drop deadline, and released to the treasury after
+/// it. The enrollment record is closed and its rent returned to the student.
+pub fn drop_class(ctx: Context<DropClass>) -> Result<()> {
+    let refund = ctx
+        .accounts
+        .enrollment
+        .drop_refund(Clock::get()?.unix_timestamp);
+    ctx.accounts.escrow.settle(
+        &ctx.accounts.school,
+        &mut ctx.accounts.enrollment,
+        &mut ctx.accounts.treasury,
+        &ctx.accounts.wallet.to_account_info(),
+        refund,
+    )?;
+    ctx.accounts.course.decrement_enrolled_students_count()
+}
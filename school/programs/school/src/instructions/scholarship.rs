@@ -0,0 +1,189 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{create_account, CreateAccount},
+};
+
+use crate::{
+    constants::StaffRole,
+    error::{MultisigError, ScholarshipError, SchoolError},
+    events::ScholarshipRedeemed,
+    Course, CourseTrait, Discount, Scholarship, ScholarshipTrait, School, SchoolTrait,
+    StaffRegistry, StaffRegistryTrait,
+};
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey, course: Option<Pubkey>)]
+pub struct IssueScholarship<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(constraint = school.get_authority() == authority.key() @ SchoolError::Unauthorized)]
+    pub school: Account<'info, School>,
+    /// The course the scholarship is scoped to, left out for a school-wide scholarship.
+    pub course_account: Option<Account<'info, Course>>,
+    #[account(
+        init,
+        payer = authority,
+        space = Scholarship::LEN,
+        seeds = [
+            b"scholarship".as_ref(),
+            school.key().as_ref(),
+            wallet.as_ref(),
+            course.as_ref().map_or(&[][..], |course| course.as_ref())
+        ],
+        bump
+    )]
+    pub scholarship: Account<'info, Scholarship>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Checks the terms of a new scholarship and, when it is scoped to `course`, that
+/// `course_account` is that course and belongs to `school`.
+pub(crate) fn validate_scholarship(
+    school: Pubkey,
+    course: Option<Pubkey>,
+    course_account: Option<&Account<Course>>,
+    discount: &Discount,
+    expires_at: i64,
+) -> Result<()> {
+    require!(discount.is_valid(), ScholarshipError::InvalidDiscount);
+    require!(
+        expires_at > Clock::get()?.unix_timestamp,
+        ScholarshipError::InvalidExpiry
+    );
+    if let Some(course) = course {
+        let course_account = course_account
+            .filter(|account| account.key() == course)
+            .ok_or(ScholarshipError::InvalidCourse)?;
+        require_keys_eq!(
+            course_account.get_school(),
+            school,
+            ScholarshipError::InvalidCourse
+        );
+    }
+    Ok(())
+}
+
+/// Creates the scholarship PDA for `wallet` and `course`, for callers that cannot derive
+/// it in their accounts struct because the scope is only known at execution time.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_scholarship<'info>(
+    school: &Account<'info, School>,
+    scholarship: &UncheckedAccount<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    wallet: Pubkey,
+    course: Option<Pubkey>,
+    discount: Discount,
+    expires_at: i64,
+) -> Result<()> {
+    let school_key = school.key();
+    let course_seed = course.as_ref().map_or(&[][..], |course| course.as_ref());
+    let (address, bump) = Pubkey::find_program_address(
+        &[
+            b"scholarship",
+            school_key.as_ref(),
+            wallet.as_ref(),
+            course_seed,
+        ],
+        &crate::ID,
+    );
+    require_keys_eq!(
+        scholarship.key(),
+        address,
+        MultisigError::ProposalAccountMismatch
+    );
+    create_account(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            CreateAccount {
+                from: payer.to_account_info(),
+                to: scholarship.to_account_info(),
+            },
+            &[&[
+                b"scholarship",
+                school_key.as_ref(),
+                wallet.as_ref(),
+                course_seed,
+                &[bump],
+            ]],
+        ),
+        Rent::get()?.minimum_balance(Scholarship::LEN),
+        Scholarship::LEN as u64,
+        &crate::ID,
+    )?;
+    let mut data = scholarship.try_borrow_mut_data()?;
+    Scholarship::new(school_key, wallet, course, discount, expires_at, bump)
+        .try_serialize(&mut &mut data[..])
+}
+
+/// Grants `wallet` a waiver on its next fee, or on `course` tuition when a course is given.
+///
+/// Schools governed by a multisig issue scholarships through an `IssueScholarship`
+/// proposal instead.
+pub fn issue_scholarship(
+    ctx: Context<IssueScholarship>,
+    wallet: Pubkey,
+    course: Option<Pubkey>,
+    discount: Discount,
+    expires_at: i64,
+) -> Result<()> {
+    validate_scholarship(
+        ctx.accounts.school.key(),
+        course,
+        ctx.accounts.course_account.as_ref(),
+        &discount,
+        expires_at,
+    )?;
+    ctx.accounts.scholarship.set_inner(Scholarship::new(
+        ctx.accounts.school.key(),
+        wallet,
+        course,
+        discount,
+        expires_at,
+        ctx.bumps.scholarship,
+    ));
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevokeScholarship<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub school: Account<'info, School>,
+    #[account(
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump = staff_registry.get_bump(),
+        constraint = staff_registry.authorizes(&school, &authority.key(), StaffRole::Bursar)
+            @ SchoolError::Unauthorized
+    )]
+    pub staff_registry: Account<'info, StaffRegistry>,
+    #[account(
+        mut,
+        close = authority,
+        constraint = scholarship.get_school() == school.key() @ ScholarshipError::NotApplicable
+    )]
+    pub scholarship: Account<'info, Scholarship>,
+}
+
+/// Closes a scholarship, used or not, so the same scope can be granted again.
+pub fn revoke_scholarship(_ctx: Context<RevokeScholarship>) -> Result<()> {
+    Ok(())
+}
+
+/// Applies an optional scholarship to `fee` and returns the amount left to pay.
+pub(crate) fn apply_scholarship(
+    scholarship: Option<&mut Account<Scholarship>>,
+    fee: u64,
+) -> Result<u64> {
+    let Some(scholarship) = scholarship else {
+        return Ok(fee);
+    };
+    let due = scholarship.redeem(fee, Clock::get()?.unix_timestamp)?;
+    emit!(ScholarshipRedeemed {
+        scholarship: scholarship.key(),
+        wallet: scholarship.get_wallet(),
+        fee,
+        waived: fee - due,
+    });
+    Ok(due)
+}
@@ -1,36 +1,257 @@
 use anchor_lang::prelude::*;
 
-use crate::{constants::SchoolType, error::SchoolError, School, SchoolTrait};
+use crate::{
+    constants::{SchoolType, MAX_NAME_LENGTH},
+    error::SchoolError,
+    events::{
+        AuthorityTransferAccepted, AuthorityTransferCancelled, AuthorityTransferProposed,
+        EnrollmentFeeUpdated, FeeMultiplierUpdated, SchoolNameUpdated,
+    },
+    FeeSchedule, FeeScheduleTrait, School, SchoolTrait, StaffRegistry, StaffRegistryTrait,
+    Treasury, TreasuryTrait,
+};
 
 #[derive(Accounts)]
-#[instruction(school_type: String)]
+#[instruction(enrollment_fee: u64, name: String, school_type: String)]
 pub struct InitializeSchool<'info> {
-    #[account(init, payer=authority, space= 8 + 32 + 8 + 4 + 4, seeds=[b"school".as_ref(), authority.key().as_ref(), &school_type.as_bytes()], bump)]
+    #[account(init, payer=authority, space= School::space(name.len()), seeds=[b"school".as_ref(), authority.key().as_ref(), school_type.as_bytes()], bump)]
     pub school: Account<'info, School>,
+    #[account(
+        init,
+        payer = authority,
+        space = StaffRegistry::LEN,
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump
+    )]
+    pub staff_registry: Account<'info, StaffRegistry>,
+    #[account(
+        init,
+        payer = authority,
+        space = Treasury::LEN,
+        seeds = [b"treasury", school.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(seeds = [b"fee_schedule"], bump = fee_schedule.get_bump())]
+    pub fee_schedule: Account<'info, FeeSchedule>,
     #[account(mut)]
     pub authority: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
+/// Creates a school with its staff registry and treasury.
+///
+/// The fee multiplier starts at the fee schedule's default for the school type.
 pub fn initialize_school(
     ctx: Context<InitializeSchool>,
     enrollment_fee: u64,
     name: String,
     school_type: String,
-    fee_multiplier: u64,
 ) -> Result<()> {
+    require!(
+        !name.is_empty() && name.len() <= MAX_NAME_LENGTH,
+        SchoolError::InvalidNameLength
+    );
     let school_type = match school_type.as_str() {
         "HighSchool" => SchoolType::HighSchool,
         "College" => SchoolType::College,
         "University" => SchoolType::University,
         _ => return Err(SchoolError::InvalidSchoolType.into()),
     };
+    let tier = ctx.accounts.fee_schedule.get_tier(school_type)?;
     let school = &mut ctx.accounts.school;
     school.set_authority(ctx.accounts.authority.key());
+    school.set_creator(ctx.accounts.authority.key());
     school.set_enrollment_fee(enrollment_fee);
     school.set_school_type(school_type);
-    school.set_fee_multiplier(fee_multiplier)?;
+    school.set_fee_multiplier(tier.default_multiplier_bps, &tier)?;
     school.set_name(name);
     school.set_bump(ctx.bumps.school);
+    ctx.accounts
+        .staff_registry
+        .set_inner(StaffRegistry::new(school.key(), ctx.bumps.staff_registry));
+    ctx.accounts
+        .treasury
+        .set_inner(Treasury::new(school.key(), ctx.bumps.treasury));
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateSchool<'info> {
+    #[account(
+        mut,
+        constraint = school.get_authority() == authority.key() @ SchoolError::Unauthorized
+    )]
+    pub school: Account<'info, School>,
+    pub authority: Signer<'info>,
+}
+
+pub fn update_enrollment_fee(ctx: Context<UpdateSchool>, new_fee: u64) -> Result<()> {
+    apply_enrollment_fee(&mut ctx.accounts.school, new_fee)
+}
+
+#[derive(Accounts)]
+pub struct UpdateFeeMultiplier<'info> {
+    #[account(
+        mut,
+        constraint = school.get_authority() == authority.key() @ SchoolError::Unauthorized
+    )]
+    pub school: Account<'info, School>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"fee_schedule"], bump = fee_schedule.get_bump())]
+    pub fee_schedule: Account<'info, FeeSchedule>,
+}
+
+pub fn update_fee_multiplier(
+    ctx: Context<UpdateFeeMultiplier>,
+    new_multiplier_bps: u64,
+) -> Result<()> {
+    apply_fee_multiplier(
+        &mut ctx.accounts.school,
+        &ctx.accounts.fee_schedule,
+        new_multiplier_bps,
+    )
+}
+
+pub(crate) fn apply_enrollment_fee(school: &mut Account<School>, new_fee: u64) -> Result<()> {
+    let old_fee = school.get_enrollment_fee();
+    school.set_enrollment_fee(new_fee);
+    emit!(EnrollmentFeeUpdated {
+        school: school.key(),
+        old_fee,
+        new_fee,
+    });
+    Ok(())
+}
+
+pub(crate) fn apply_fee_multiplier(
+    school: &mut Account<School>,
+    fee_schedule: &FeeSchedule,
+    new_multiplier: u64,
+) -> Result<()> {
+    let tier = fee_schedule.get_tier(school.get_school_type())?;
+    let old_multiplier = school.get_fee_multiplier();
+    school.set_fee_multiplier(new_multiplier, &tier)?;
+    emit!(FeeMultiplierUpdated {
+        school: school.key(),
+        old_multiplier,
+        new_multiplier,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct QuoteEnrollmentFee<'info> {
+    pub school: Account<'info, School>,
+}
+
+/// Returns the current enrollment fee through return data, in the smallest unit of the
+/// school's payment currency.
+pub fn quote_enrollment_fee(ctx: Context<QuoteEnrollmentFee>) -> Result<u64> {
+    ctx.accounts.school.calculate_enrollment_fees()
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct UpdateSchoolName<'info> {
+    #[account(
+        mut,
+        constraint = school.get_authority() == authority.key() @ SchoolError::Unauthorized,
+        realloc = School::space(name.len()),
+        realloc::payer = authority,
+        realloc::zero = false
+    )]
+    pub school: Account<'info, School>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Renames the school, resizing the account to fit the new name.
+///
+/// A longer name is paid for by the authority and a shorter one refunds the difference.
+pub fn update_school_name(ctx: Context<UpdateSchoolName>, name: String) -> Result<()> {
+    apply_school_name(&mut ctx.accounts.school, name)
+}
+
+pub(crate) fn apply_school_name(school: &mut Account<School>, name: String) -> Result<()> {
+    require!(
+        !name.is_empty() && name.len() <= MAX_NAME_LENGTH,
+        SchoolError::InvalidNameLength
+    );
+    let old_name = school.get_name().to_string();
+    school.set_name(name.clone());
+    emit!(SchoolNameUpdated {
+        school: school.key(),
+        old_name,
+        new_name: name,
+    });
+    Ok(())
+}
+
+/// Proposes `new_authority` as the next school authority.
+///
+/// Nothing changes until the proposed key signs `accept_authority`, so a mistyped key
+/// can simply be cancelled or replaced by another proposal.
+pub fn propose_authority(ctx: Context<UpdateSchool>, new_authority: Pubkey) -> Result<()> {
+    apply_pending_authority(&mut ctx.accounts.school, new_authority)
+}
+
+pub(crate) fn apply_pending_authority(
+    school: &mut Account<School>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    school.set_pending_authority(Some(new_authority));
+    emit!(AuthorityTransferProposed {
+        school: school.key(),
+        authority: school.get_authority(),
+        pending_authority: new_authority,
+    });
+    Ok(())
+}
+
+pub fn cancel_authority_transfer(ctx: Context<UpdateSchool>) -> Result<()> {
+    apply_cancel_authority_transfer(&mut ctx.accounts.school)
+}
+
+pub(crate) fn apply_cancel_authority_transfer(school: &mut Account<School>) -> Result<()> {
+    let pending_authority = school
+        .get_pending_authority()
+        .ok_or(SchoolError::NoPendingAuthority)?;
+    school.set_pending_authority(None);
+    emit!(AuthorityTransferCancelled {
+        school: school.key(),
+        authority: school.get_authority(),
+        pending_authority,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        constraint = school.get_pending_authority() == Some(pending_authority.key())
+            @ SchoolError::NotPendingAuthority
+    )]
+    pub school: Account<'info, School>,
+    pub pending_authority: Signer<'info>,
+}
+
+/// Completes an authority transfer, signed by the proposed authority.
+///
+/// The school PDA keeps being derived from its creator, so its address and the
+/// accounts seeded from it are unaffected by the handover.
+pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let school = &mut ctx.accounts.school;
+    let old_authority = school.get_authority();
+    let new_authority = ctx.accounts.pending_authority.key();
+    school.set_authority(new_authority);
+    school.set_pending_authority(None);
+    emit!(AuthorityTransferAccepted {
+        school: school.key(),
+        old_authority,
+        new_authority,
+    });
     Ok(())
 }
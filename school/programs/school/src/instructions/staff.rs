@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::StaffRole,
+    error::SchoolError,
+    events::{StaffRoleGranted, StaffRoleRevoked},
+    School, SchoolTrait, StaffRegistry, StaffRegistryTrait,
+};
+
+#[derive(Accounts)]
+pub struct UpdateStaff<'info> {
+    pub authority: Signer<'info>,
+    #[account(constraint = school.get_authority() == authority.key() @ SchoolError::Unauthorized)]
+    pub school: Account<'info, School>,
+    #[account(
+        mut,
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump = staff_registry.get_bump()
+    )]
+    pub staff_registry: Account<'info, StaffRegistry>,
+}
+
+pub fn grant_staff_role(ctx: Context<UpdateStaff>, member: Pubkey, role: StaffRole) -> Result<()> {
+    apply_grant_role(&mut ctx.accounts.staff_registry, member, role)
+}
+
+pub fn revoke_staff_role(ctx: Context<UpdateStaff>, member: Pubkey, role: StaffRole) -> Result<()> {
+    apply_revoke_role(&mut ctx.accounts.staff_registry, member, role)
+}
+
+pub(crate) fn apply_grant_role(
+    staff_registry: &mut StaffRegistry,
+    member: Pubkey,
+    role: StaffRole,
+) -> Result<()> {
+    staff_registry.grant_role(member, role)?;
+    emit!(StaffRoleGranted {
+        school: staff_registry.get_school(),
+        member,
+        role,
+    });
+    Ok(())
+}
+
+pub(crate) fn apply_revoke_role(
+    staff_registry: &mut StaffRegistry,
+    member: Pubkey,
+    role: StaffRole,
+) -> Result<()> {
+    staff_registry.revoke_role(&member, role)?;
+    emit!(StaffRoleRevoked {
+        school: staff_registry.get_school(),
+        member,
+        role,
+    });
+    Ok(())
+}
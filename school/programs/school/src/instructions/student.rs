@@ -0,0 +1,300 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{create_metadata_accounts_v3, CreateMetadataAccountsV3, Metadata},
+    token::{
+        approve, burn, close_account, freeze_account, mint_to, set_authority,
+        spl_token::instruction::AuthorityType, thaw_account, Approve, Burn, CloseAccount,
+        FreezeAccount, Mint, MintTo, SetAuthority, ThawAccount, Token, TokenAccount,
+    },
+};
+use mpl_token_metadata::{accounts::Metadata as MetaDataAccount, types::DataV2};
+
+use super::{
+    mintnft::validate_nft_meta_data_attributes, scholarship::apply_scholarship, treasury::*,
+};
+use crate::{
+    constants::{NftMetaDataAttributes, StaffRole, STUDENT_ID_SYMBOL},
+    error::{ScholarshipError, SchoolError, StudentError},
+    Scholarship, ScholarshipTrait, School, SchoolTrait, StaffRegistry, StaffRegistryTrait, Student,
+    StudentTrait, Treasury, TreasuryTrait,
+};
+
+#[derive(Accounts)]
+pub struct Enroll<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    #[account(mut)]
+    pub school: Box<Account<'info, School>>,
+    #[account(
+        init,
+        payer = wallet,
+        space = Student::LEN,
+        seeds = [b"student", school.key().as_ref(), wallet.key().as_ref()],
+        bump
+    )]
+    pub student: Box<Account<'info, Student>>,
+    #[account(
+        mut,
+        seeds = [b"treasury", school.key().as_ref()],
+        bump = treasury.get_bump()
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+    pub payment: FeePayment<'info>,
+    #[account(
+        mut,
+        constraint = scholarship.covers(school.key(), wallet.key(), None)
+            @ ScholarshipError::NotApplicable
+    )]
+    pub scholarship: Option<Box<Account<'info, Scholarship>>>,
+    #[account(
+        init,
+        payer = wallet,
+        seeds = [b"student_id", school.key().as_ref(), wallet.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = school.key(),
+        mint::freeze_authority = school.key()
+    )]
+    pub id_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = wallet,
+        associated_token::mint = id_mint,
+        associated_token::authority = wallet
+    )]
+    pub id_token_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK: Address is derived using a known PDA
+    #[account(mut, address = MetaDataAccount::find_pda(&id_mint.key()).0)]
+    pub id_metadata_account: AccountInfo<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> Enroll<'info> {
+    /// Issues the soulbound student ID NFT to the student's wallet.
+    ///
+    /// The school PDA is the mint and freeze authority. Before the token account is frozen the
+    /// student delegates the token to the school and hands it the close authority, which is what
+    /// later lets `revoke_student_id` burn the ID without the student's signature.
+    fn issue_student_id(&self, student_id: u64, uri: &str) -> Result<()> {
+        let name = format!("Student ID #{}", student_id);
+        validate_nft_meta_data_attributes(NftMetaDataAttributes {
+            name: name.clone(),
+            symbol: STUDENT_ID_SYMBOL.to_string(),
+            uri: uri.to_string(),
+        })?;
+
+        let seeds = self.school.signer_seeds();
+        let signer = &[&seeds[..]];
+        let school = self.school.to_account_info();
+        let token_program = self.token_program.to_account_info();
+
+        mint_to(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                MintTo {
+                    mint: self.id_mint.to_account_info(),
+                    to: self.id_token_account.to_account_info(),
+                    authority: school.clone(),
+                },
+                signer,
+            ),
+            1,
+        )?;
+
+        create_metadata_accounts_v3(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.to_account_info(),
+                CreateMetadataAccountsV3 {
+                    metadata: self.id_metadata_account.to_account_info(),
+                    mint: self.id_mint.to_account_info(),
+                    mint_authority: school.clone(),
+                    update_authority: school.clone(),
+                    payer: self.wallet.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                    rent: self.rent.to_account_info(),
+                },
+                signer,
+            ),
+            DataV2 {
+                name,
+                symbol: STUDENT_ID_SYMBOL.to_string(),
+                uri: uri.to_string(),
+                seller_fee_basis_points: 0,
+                creators: None,
+                collection: None,
+                uses: None,
+            },
+            false,
+            true,
+            None,
+        )?;
+
+        approve(
+            CpiContext::new(
+                token_program.clone(),
+                Approve {
+                    to: self.id_token_account.to_account_info(),
+                    delegate: school.clone(),
+                    authority: self.wallet.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        set_authority(
+            CpiContext::new(
+                token_program.clone(),
+                SetAuthority {
+                    account_or_mint: self.id_token_account.to_account_info(),
+                    current_authority: self.wallet.to_account_info(),
+                },
+            ),
+            AuthorityType::CloseAccount,
+            Some(self.school.key()),
+        )?;
+
+        freeze_account(CpiContext::new_with_signer(
+            token_program,
+            FreezeAccount {
+                account: self.id_token_account.to_account_info(),
+                mint: self.id_mint.to_account_info(),
+                authority: school,
+            },
+            signer,
+        ))
+    }
+}
+
+impl<'info> Enroll<'info> {
+    /// Enrollment fee owed by the wallet, after redeeming its scholarship if one is given.
+    pub fn enrollment_fee(&mut self) -> Result<u64> {
+        apply_scholarship(
+            self.scholarship.as_deref_mut(),
+            self.school.calculate_enrollment_fees()?,
+        )
+    }
+
+    /// Collects `amount` into the treasury and admits the wallet as the next student.
+    pub fn admit(&mut self, bumps: &EnrollBumps, uri: &str, amount: u64) -> Result<()> {
+        self.payment.collect(
+            &self.school,
+            &mut self.treasury,
+            &self.wallet,
+            &self.system_program,
+            amount,
+        )?;
+
+        let student_id = self.school.student_count();
+        self.issue_student_id(student_id, uri)?;
+
+        let clock = Clock::get()?;
+        self.student.set_inner(Student::new(
+            self.school.key(),
+            self.wallet.key(),
+            student_id,
+            self.id_mint.key(),
+            clock.unix_timestamp,
+            amount,
+            bumps.student,
+        ));
+        self.school.increment_student_count()
+    }
+}
+
+/// Enrolls the signing wallet as a student of the school.
+///
+/// The enrollment fee from `calculate_enrollment_fees` is transferred from the student
+/// to the school treasury, the student is numbered by the school's `student_count` and
+/// receives a frozen student ID NFT whose metadata points at `uri`.
+/// Enrolling twice fails because the student PDA already exists.
+pub fn enroll(ctx: Context<Enroll>, uri: String) -> Result<()> {
+    let fee = ctx.accounts.enrollment_fee()?;
+    ctx.accounts.admit(&ctx.bumps, &uri, fee)
+}
+
+#[derive(Accounts)]
+pub struct RevokeStudentId<'info> {
+    pub authority: Signer<'info>,
+    pub school: Box<Account<'info, School>>,
+    #[account(
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump = staff_registry.get_bump(),
+        constraint = staff_registry.authorizes(&school, &authority.key(), StaffRole::Registrar)
+            @ SchoolError::Unauthorized
+    )]
+    pub staff_registry: Box<Account<'info, StaffRegistry>>,
+    #[account(
+        mut,
+        seeds = [b"student", school.key().as_ref(), wallet.key().as_ref()],
+        bump = student.get_bump()
+    )]
+    pub student: Box<Account<'info, Student>>,
+    /// Receives the rent of the closed ID token account.
+    #[account(mut)]
+    pub wallet: SystemAccount<'info>,
+    #[account(
+        mut,
+        address = student.get_id_mint() @ StudentError::StudentIdNotIssued
+    )]
+    pub id_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = id_mint,
+        associated_token::authority = wallet
+    )]
+    pub id_token_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Revokes a student's ID NFT when they leave the school.
+///
+/// The school thaws the token account, burns the ID as the student's delegate
+/// and closes the token account as its close authority, returning the rent to the student.
+pub fn revoke_student_id(ctx: Context<RevokeStudentId>) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let seeds = accounts.school.signer_seeds();
+    let signer = &[&seeds[..]];
+    let school = accounts.school.to_account_info();
+    let token_program = accounts.token_program.to_account_info();
+
+    thaw_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        ThawAccount {
+            account: accounts.id_token_account.to_account_info(),
+            mint: accounts.id_mint.to_account_info(),
+            authority: school.clone(),
+        },
+        signer,
+    ))?;
+
+    burn(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            Burn {
+                mint: accounts.id_mint.to_account_info(),
+                from: accounts.id_token_account.to_account_info(),
+                authority: school.clone(),
+            },
+            signer,
+        ),
+        1,
+    )?;
+
+    close_account(CpiContext::new_with_signer(
+        token_program,
+        CloseAccount {
+            account: accounts.id_token_account.to_account_info(),
+            destination: accounts.wallet.to_account_info(),
+            authority: school,
+        },
+        signer,
+    ))?;
+
+    ctx.accounts.student.set_id_mint(Pubkey::default());
+    Ok(())
+}
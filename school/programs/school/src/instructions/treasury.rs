@@ -0,0 +1,393 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::{
+    associated_token::{create_idempotent, get_associated_token_address, AssociatedToken, Create},
+    token::{transfer_checked, Mint, Token, TokenAccount, TransferChecked},
+};
+
+use crate::{
+    error::{SchoolError, TreasuryError},
+    events::{PaymentMintUpdated, TreasuryTokensWithdrawn, TreasuryWithdrawn},
+    School, SchoolTrait, Treasury, TreasuryTrait,
+};
+
+/// Transfers `amount` from a paying wallet into the treasury and records it.
+pub(crate) fn deposit<'info>(
+    treasury: &mut Account<'info, Treasury>,
+    from: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let cpi_context = CpiContext::new(
+        system_program,
+        Transfer {
+            from,
+            to: treasury.to_account_info(),
+        },
+    );
+    transfer(cpi_context, amount)?;
+    treasury.record_deposit(amount)
+}
+
+/// Moves `amount` out of the treasury, never below its rent-exempt minimum.
+pub(crate) fn withdraw<'info>(
+    treasury: &mut Account<'info, Treasury>,
+    recipient: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, TreasuryError::InvalidAmount);
+    let available = treasury
+        .get_lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(Treasury::LEN));
+    require!(amount <= available, TreasuryError::InsufficientFunds);
+    treasury.sub_lamports(amount)?;
+    recipient.add_lamports(amount)?;
+    treasury.record_withdrawal(amount)?;
+    emit!(TreasuryWithdrawn {
+        school: treasury.get_school(),
+        recipient: recipient.key(),
+        amount,
+        total_withdrawn: treasury.get_total_withdrawn(),
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    pub authority: Signer<'info>,
+    #[account(constraint = school.get_authority() == authority.key() @ SchoolError::Unauthorized)]
+    pub school: Account<'info, School>,
+    #[account(
+        mut,
+        seeds = [b"treasury", school.key().as_ref()],
+        bump = treasury.get_bump()
+    )]
+    pub treasury: Account<'info, Treasury>,
+    /// CHECK: Any account can receive the withdrawn lamports.
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+}
+
+/// Withdraws collected fees to `recipient`, signed by the school authority.
+///
+/// Schools governed by a multisig withdraw through a `WithdrawTreasury` proposal instead.
+pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    withdraw(
+        &mut ctx.accounts.treasury,
+        &ctx.accounts.recipient.to_account_info(),
+        amount,
+    )
+}
+
+/// Token accounts a fee is paid with when the school accepts an SPL payment mint.
+///
+/// All of them are left out while the school is paid in SOL.
+#[derive(Accounts)]
+pub struct FeePayment<'info> {
+    pub payment_mint: Option<Box<Account<'info, Mint>>>,
+    #[account(mut)]
+    pub payer_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut)]
+    pub treasury_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+impl<'info> FeePayment<'info> {
+    /// Moves `amount` from `payer` into the treasury in the school's payment currency.
+    pub fn collect(
+        &self,
+        school: &School,
+        treasury: &mut Account<'info, Treasury>,
+        payer: &Signer<'info>,
+        system_program: &Program<'info, System>,
+        amount: u64,
+    ) -> Result<()> {
+        self.collect_in(
+            school.get_payment_mint(),
+            school,
+            treasury,
+            payer,
+            system_program,
+            amount,
+        )
+    }
+
+    /// Moves `amount` from `payer` into the treasury in `currency`, which may be a mint the
+    /// school has since switched away from. Only the current mint counts towards the
+    /// treasury's token totals.
+    pub fn collect_in(
+        &self,
+        currency: Option<Pubkey>,
+        school: &School,
+        treasury: &mut Account<'info, Treasury>,
+        payer: &Signer<'info>,
+        system_program: &Program<'info, System>,
+        amount: u64,
+    ) -> Result<()> {
+        let Some(payment_mint) = currency else {
+            return deposit(
+                treasury,
+                payer.to_account_info(),
+                system_program.to_account_info(),
+                amount,
+            );
+        };
+        let Some(to) = &self.treasury_token_account else {
+            return err!(TreasuryError::MissingPaymentAccounts);
+        };
+        require_keys_eq!(
+            to.key(),
+            get_associated_token_address(&treasury.key(), &payment_mint),
+            TreasuryError::InvalidTreasuryTokenAccount
+        );
+        self.transfer_tokens(payment_mint, payer, to.to_account_info(), amount)?;
+        if school.get_payment_mint() != Some(payment_mint) {
+            return Ok(());
+        }
+        treasury.record_token_deposit(amount)
+    }
+
+    /// Moves `amount` of `payment_mint` from the payer's token account into the associated
+    /// token account of `escrow_authority`, creating it first if needed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn escrow_tokens(
+        &self,
+        payment_mint: Pubkey,
+        payer: &Signer<'info>,
+        escrow_authority: AccountInfo<'info>,
+        escrow_token_account: Option<&UncheckedAccount<'info>>,
+        associated_token_program: Option<&Program<'info, AssociatedToken>>,
+        system_program: &Program<'info, System>,
+        amount: u64,
+    ) -> Result<()> {
+        let (
+            Some(escrow_token_account),
+            Some(associated_token_program),
+            Some(mint),
+            Some(token_program),
+        ) = (
+            escrow_token_account,
+            associated_token_program,
+            &self.payment_mint,
+            &self.token_program,
+        )
+        else {
+            return err!(TreasuryError::MissingPaymentAccounts);
+        };
+        require_keys_eq!(
+            escrow_token_account.key(),
+            get_associated_token_address(&escrow_authority.key(), &payment_mint),
+            TreasuryError::InvalidEscrowTokenAccount
+        );
+        create_idempotent(CpiContext::new(
+            associated_token_program.to_account_info(),
+            Create {
+                payer: payer.to_account_info(),
+                associated_token: escrow_token_account.to_account_info(),
+                authority: escrow_authority,
+                mint: mint.to_account_info(),
+                system_program: system_program.to_account_info(),
+                token_program: token_program.to_account_info(),
+            },
+        ))?;
+        self.transfer_tokens(
+            payment_mint,
+            payer,
+            escrow_token_account.to_account_info(),
+            amount,
+        )
+    }
+
+    /// Moves `amount` of `payment_mint` from the payer's token account to `to`.
+    pub fn transfer_tokens(
+        &self,
+        payment_mint: Pubkey,
+        payer: &Signer<'info>,
+        to: AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        let (Some(mint), Some(from), Some(token_program)) = (
+            &self.payment_mint,
+            &self.payer_token_account,
+            &self.token_program,
+        ) else {
+            return err!(TreasuryError::MissingPaymentAccounts);
+        };
+        require_keys_eq!(mint.key(), payment_mint, TreasuryError::InvalidPaymentMint);
+        if amount == 0 {
+            return Ok(());
+        }
+        transfer_checked(
+            CpiContext::new(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: from.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to,
+                    authority: payer.to_account_info(),
+                },
+            ),
+            amount,
+            mint.decimals,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct UpdatePaymentMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        constraint = school.get_authority() == authority.key() @ SchoolError::Unauthorized
+    )]
+    pub school: Account<'info, School>,
+    #[account(
+        mut,
+        seeds = [b"treasury", school.key().as_ref()],
+        bump = treasury.get_bump()
+    )]
+    pub treasury: Account<'info, Treasury>,
+    pub payment_mint: Option<Account<'info, Mint>>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = payment_mint,
+        associated_token::authority = treasury
+    )]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Sets the SPL mint enrollment fees and tuition are paid in, or SOL when no mint is given.
+///
+/// Creates the treasury's token account for the new mint. Switching away from a mint is only
+/// allowed once every token collected in it has been withdrawn, and restarts the token totals.
+pub fn update_payment_mint(ctx: Context<UpdatePaymentMint>) -> Result<()> {
+    let new_mint = ctx.accounts.payment_mint.as_ref().map(|mint| mint.key());
+    apply_payment_mint(
+        &mut ctx.accounts.school,
+        &mut ctx.accounts.treasury,
+        new_mint,
+        ctx.accounts.treasury_token_account.is_some(),
+    )
+}
+
+/// Switches the school to `new_mint`, whose treasury token account must already exist.
+pub(crate) fn apply_payment_mint(
+    school: &mut Account<School>,
+    treasury: &mut Account<Treasury>,
+    new_mint: Option<Pubkey>,
+    has_treasury_token_account: bool,
+) -> Result<()> {
+    // Withdrawals can exceed the totals when the mint's account still held tokens released
+    // from escrows opened before an earlier switch away from it.
+    require!(
+        treasury.get_token_withdrawn() >= treasury.get_token_collected(),
+        TreasuryError::TokenBalanceOutstanding
+    );
+    require!(
+        new_mint.is_none() || has_treasury_token_account,
+        TreasuryError::MissingPaymentAccounts
+    );
+    let old_mint = school.get_payment_mint();
+    school.set_payment_mint(new_mint);
+    treasury.reset_token_totals();
+    emit!(PaymentMintUpdated {
+        school: school.key(),
+        old_mint,
+        new_mint,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasuryTokens<'info> {
+    pub authority: Signer<'info>,
+    #[account(constraint = school.get_authority() == authority.key() @ SchoolError::Unauthorized)]
+    pub school: Account<'info, School>,
+    #[account(
+        mut,
+        seeds = [b"treasury", school.key().as_ref()],
+        bump = treasury.get_bump()
+    )]
+    pub treasury: Account<'info, Treasury>,
+    /// The current payment mint, or a previous one whose tokens are still in the treasury.
+    pub payment_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = treasury
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = payment_mint)]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Withdraws collected payment tokens to `recipient_token_account`, signed by the school
+/// authority.
+///
+/// Tuition escrowed before a payment mint change is still released in its original mint,
+/// so tokens of previous payment mints can be withdrawn too.
+///
+/// Schools governed by a multisig withdraw through a `WithdrawTreasuryTokens` proposal instead.
+pub fn withdraw_treasury_tokens(ctx: Context<WithdrawTreasuryTokens>, amount: u64) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    withdraw_tokens(
+        &accounts.school,
+        &mut accounts.treasury,
+        &accounts.payment_mint,
+        &accounts.treasury_token_account,
+        &accounts.recipient_token_account,
+        &accounts.token_program,
+        amount,
+    )
+}
+
+/// Moves `amount` tokens of `payment_mint` out of the treasury's token account, recording
+/// it in the token totals when it is the school's current payment mint.
+pub(crate) fn withdraw_tokens<'info>(
+    school: &School,
+    treasury: &mut Account<'info, Treasury>,
+    payment_mint: &Account<'info, Mint>,
+    treasury_token_account: &Account<'info, TokenAccount>,
+    recipient_token_account: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, TreasuryError::InvalidAmount);
+    let school_key = treasury.get_school();
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: treasury_token_account.to_account_info(),
+                mint: payment_mint.to_account_info(),
+                to: recipient_token_account.to_account_info(),
+                authority: treasury.to_account_info(),
+            },
+            &[&treasury.signer_seeds(&school_key)],
+        ),
+        amount,
+        payment_mint.decimals,
+    )?;
+    if school.get_payment_mint() == Some(payment_mint.key()) {
+        treasury.record_token_withdrawal(amount)?;
+    }
+    emit!(TreasuryTokensWithdrawn {
+        school: school_key,
+        mint: payment_mint.key(),
+        recipient: recipient_token_account.key(),
+        amount,
+        total_withdrawn: treasury.get_token_withdrawn(),
+    });
+    Ok(())
+}
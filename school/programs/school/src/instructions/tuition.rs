@@ -0,0 +1,414 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address,
+    token::{
+        close_account, transfer_checked, CloseAccount, Mint, Token, TokenAccount, TransferChecked,
+    },
+};
+
+use super::treasury::withdraw;
+use crate::{
+    error::{CourseError, TreasuryError},
+    events::{CourseRefundSkipped, TuitionSettled},
+    Course, CourseEnrollment, CourseEnrollmentTrait, CourseTrait, School, SchoolTrait, Student,
+    StudentTrait, Treasury, TreasuryTrait,
+};
+
+/// Token accounts needed to pay out tuition escrowed in an SPL token.
+///
+/// All of them are left out when the tuition was escrowed in lamports, or has already been
+/// released.
+#[derive(Accounts)]
+pub struct TuitionEscrow<'info> {
+    pub escrow_mint: Option<Box<Account<'info, Mint>>>,
+    #[account(mut)]
+    pub escrow_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut)]
+    pub refund_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut)]
+    pub treasury_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+impl<'info> TuitionEscrow<'info> {
+    /// Empties the enrollment's escrow, refunding `refund` to the student's wallet and
+    /// releasing the rest to the treasury.
+    pub fn settle(
+        &self,
+        school: &School,
+        enrollment: &mut Account<'info, CourseEnrollment>,
+        treasury: &mut Account<'info, Treasury>,
+        wallet: &AccountInfo<'info>,
+        refund: u64,
+    ) -> Result<()> {
+        let escrowed = enrollment.take_escrow();
+        let released = escrowed
+            .checked_sub(refund)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        match enrollment.get_escrow_mint() {
+            // Releasing the tuition already closed the escrow token account.
+            Some(_) if escrowed == 0 && self.escrow_token_account.is_none() => {}
+            None => {
+                enrollment.sub_lamports(escrowed)?;
+                wallet.add_lamports(refund)?;
+                treasury.add_lamports(released)?;
+                treasury.record_deposit(released)?;
+            }
+            Some(escrow_mint) => {
+                self.settle_tokens(
+                    school,
+                    enrollment,
+                    treasury,
+                    wallet,
+                    escrow_mint,
+                    refund,
+                    released,
+                )?;
+            }
+        }
+        emit!(TuitionSettled {
+            enrollment: enrollment.key(),
+            wallet: wallet.key(),
+            refunded: refund,
+            released,
+        });
+        Ok(())
+    }
+
+    /// Whether `refund_in_full` can currently pay back `enrollment` with these accounts: the
+    /// escrow is in the mint they are for, and the treasury still holds whatever had already
+    /// been released to it.
+    pub fn can_refund_in_full(
+        &self,
+        enrollment: &CourseEnrollment,
+        treasury: &Account<'info, Treasury>,
+    ) -> Result<bool> {
+        let released = enrollment
+            .get_tuition_paid()
+            .checked_sub(enrollment.get_escrowed())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        let Some(escrow_mint) = enrollment.get_escrow_mint() else {
+            let available = treasury
+                .get_lamports()
+                .saturating_sub(Rent::get()?.minimum_balance(Treasury::LEN));
+            return Ok(released <= available);
+        };
+        if self.escrow_mint.as_ref().map(|mint| mint.key()) != Some(escrow_mint) {
+            return Ok(false);
+        }
+        if released == 0 {
+            return Ok(true);
+        }
+        let Some(from) = &self.treasury_token_account else {
+            return err!(TreasuryError::MissingPaymentAccounts);
+        };
+        require_keys_eq!(
+            from.key(),
+            get_associated_token_address(&treasury.key(), &escrow_mint),
+            TreasuryError::InvalidTreasuryTokenAccount
+        );
+        Ok(released <= from.amount)
+    }
+
+    /// Returns the whole tuition to the student: whatever is still escrowed, and out of the
+    /// treasury whatever had already been released to it, in the mint it was paid in.
+    pub fn refund_in_full(
+        &self,
+        school: &School,
+        enrollment: &mut Account<'info, CourseEnrollment>,
+        treasury: &mut Account<'info, Treasury>,
+        wallet: &AccountInfo<'info>,
+    ) -> Result<()> {
+        let escrowed = enrollment.get_escrowed();
+        let released = enrollment
+            .get_tuition_paid()
+            .checked_sub(escrowed)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        if escrowed > 0 {
+            self.settle(school, enrollment, treasury, wallet, escrowed)?;
+        }
+        if released == 0 {
+            return Ok(());
+        }
+        let Some(escrow_mint) = enrollment.get_escrow_mint() else {
+            return withdraw(treasury, wallet, released);
+        };
+        let (Some(mint), Some(from), Some(to), Some(token_program)) = (
+            &self.escrow_mint,
+            &self.treasury_token_account,
+            &self.refund_token_account,
+            &self.token_program,
+        ) else {
+            return err!(TreasuryError::MissingPaymentAccounts);
+        };
+        require_keys_eq!(mint.key(), escrow_mint, TreasuryError::InvalidPaymentMint);
+        require_keys_eq!(
+            from.key(),
+            get_associated_token_address(&treasury.key(), &escrow_mint),
+            TreasuryError::InvalidTreasuryTokenAccount
+        );
+        require_keys_eq!(
+            to.owner,
+            wallet.key(),
+            TreasuryError::InvalidRefundTokenAccount
+        );
+        let school_key = treasury.get_school();
+        transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: from.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: treasury.to_account_info(),
+                },
+                &[&treasury.signer_seeds(&school_key)],
+            ),
+            released,
+            mint.decimals,
+        )?;
+        // Token totals only track the current payment mint.
+        if school.get_payment_mint() == Some(escrow_mint) {
+            treasury.record_token_withdrawal(released)?;
+        }
+        Ok(())
+    }
+
+    /// Pays out the escrow token account and closes it, returning its rent to the wallet
+    /// that opened it at registration.
+    #[allow(clippy::too_many_arguments)]
+    fn settle_tokens(
+        &self,
+        school: &School,
+        enrollment: &Account<'info, CourseEnrollment>,
+        treasury: &mut Account<'info, Treasury>,
+        wallet: &AccountInfo<'info>,
+        escrow_mint: Pubkey,
+        refund: u64,
+        released: u64,
+    ) -> Result<()> {
+        let (Some(mint), Some(escrow_token_account), Some(token_program)) = (
+            &self.escrow_mint,
+            &self.escrow_token_account,
+            &self.token_program,
+        ) else {
+            return err!(TreasuryError::MissingPaymentAccounts);
+        };
+        require_keys_eq!(mint.key(), escrow_mint, TreasuryError::InvalidPaymentMint);
+        require_keys_eq!(
+            escrow_token_account.key(),
+            get_associated_token_address(&enrollment.key(), &escrow_mint),
+            TreasuryError::InvalidEscrowTokenAccount
+        );
+        let seeds = enrollment.signer_seeds();
+        let signer = &[&seeds[..]];
+        let pay_out = |to: AccountInfo<'info>, amount: u64| {
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    TransferChecked {
+                        from: escrow_token_account.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to,
+                        authority: enrollment.to_account_info(),
+                    },
+                    signer,
+                ),
+                amount,
+                mint.decimals,
+            )
+        };
+
+        if refund > 0 {
+            let Some(to) = &self.refund_token_account else {
+                return err!(TreasuryError::MissingPaymentAccounts);
+            };
+            require_keys_eq!(
+                to.owner,
+                wallet.key(),
+                TreasuryError::InvalidRefundTokenAccount
+            );
+            pay_out(to.to_account_info(), refund)?;
+        }
+        if released > 0 {
+            let Some(to) = &self.treasury_token_account else {
+                return err!(TreasuryError::MissingPaymentAccounts);
+            };
+            require_keys_eq!(
+                to.key(),
+                get_associated_token_address(&treasury.key(), &escrow_mint),
+                TreasuryError::InvalidTreasuryTokenAccount
+            );
+            pay_out(to.to_account_info(), released)?;
+            // Token totals only track the current payment mint; tuition escrowed in a mint
+            // the school has since moved away from still lands in that mint's treasury
+            // account, where it can be withdrawn.
+            if school.get_payment_mint() == Some(escrow_mint) {
+                treasury.record_token_deposit(released)?;
+            }
+        }
+        close_account(CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            CloseAccount {
+                account: escrow_token_account.to_account_info(),
+                destination: wallet.clone(),
+                authority: enrollment.to_account_info(),
+            },
+            signer,
+        ))
+    }
+}
+
+#[derive(Accounts)]
+pub struct ReleaseTuition<'info> {
+    pub school: Account<'info, School>,
+    #[account(
+        constraint = course.get_school() == school.key() @ CourseError::CourseNotInSchool
+    )]
+    pub course: Account<'info, Course>,
+    #[account(
+        seeds = [b"student", school.key().as_ref(), wallet.key().as_ref()],
+        bump = student.get_bump()
+    )]
+    pub student: Account<'info, Student>,
+    #[account(mut, address = student.get_wallet())]
+    pub wallet: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"course_enrollment", course.key().as_ref(), student.key().as_ref()],
+        bump = enrollment.get_bump()
+    )]
+    pub enrollment: Account<'info, CourseEnrollment>,
+    #[account(
+        mut,
+        seeds = [b"treasury", school.key().as_ref()],
+        bump = treasury.get_bump()
+    )]
+    pub treasury: Account<'info, Treasury>,
+    pub escrow: TuitionEscrow<'info>,
+}
+
+/// Moves a registration's escrowed tuition into the treasury once the add/drop deadline
+/// the student registered under has passed.
+///
+/// Anyone can crank this. The student's wallet only receives the rent of the escrow token
+/// account, when there is one.
+pub fn release_tuition(ctx: Context<ReleaseTuition>) -> Result<()> {
+    require!(
+        !ctx.accounts.course.is_cancelled(),
+        CourseError::CourseCancelled
+    );
+    require!(
+        Clock::get()?.unix_timestamp > ctx.accounts.enrollment.get_add_drop_deadline(),
+        CourseError::AddDropWindowOpen
+    );
+    require!(
+        ctx.accounts.enrollment.get_escrowed() > 0,
+        CourseError::NothingEscrowed
+    );
+    ctx.accounts.escrow.settle(
+        &ctx.accounts.school,
+        &mut ctx.accounts.enrollment,
+        &mut ctx.accounts.treasury,
+        &ctx.accounts.wallet.to_account_info(),
+        0,
+    )
+}
+
+#[derive(Accounts)]
+pub struct ProcessCourseRefunds<'info> {
+    pub school: Account<'info, School>,
+    #[account(
+        mut,
+        constraint = course.get_school() == school.key() @ CourseError::CourseNotInSchool,
+        constraint = course.is_cancelled() @ CourseError::CourseNotCancelled
+    )]
+    pub course: Account<'info, Course>,
+    #[account(
+        mut,
+        seeds = [b"treasury", school.key().as_ref()],
+        bump = treasury.get_bump()
+    )]
+    pub treasury: Account<'info, Treasury>,
+    /// Shared by the whole batch; `escrow_token_account` and `refund_token_account` are left
+    /// out and passed per enrollment instead.
+    pub escrow: TuitionEscrow<'info>,
+}
+
+/// Refunds enrolled students of a cancelled course in full and closes their enrollment
+/// records, returning the rent to the students.
+///
+/// Each enrollment is passed as writable remaining accounts `[enrollment, student, wallet]`,
+/// followed by its escrow token account and the wallet's token account when its tuition was
+/// paid in an SPL token. Anyone can crank this, over as many transactions as the course needs.
+///
+/// Enrollments that cannot be refunded yet, because their tuition was paid in another mint
+/// than `escrow.escrow_mint` or the treasury no longer holds the part already released to it,
+/// are left open and reported with `CourseRefundSkipped` rather than failing the batch.
+pub fn process_course_refunds<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ProcessCourseRefunds<'info>>,
+) -> Result<()> {
+    let course = &mut ctx.accounts.course;
+    let mut remaining = ctx.remaining_accounts.iter();
+    while let Some(enrollment) = remaining.next() {
+        let mut enrollment = Account::<CourseEnrollment>::try_from(enrollment)?;
+        require_keys_eq!(
+            enrollment.get_course(),
+            course.key(),
+            CourseError::EnrollmentNotInCourse
+        );
+        let (Some(student), Some(wallet)) = (remaining.next(), remaining.next()) else {
+            return err!(ErrorCode::AccountNotEnoughKeys);
+        };
+        let student = Account::<Student>::try_from(student)?;
+        require_keys_eq!(
+            student.key(),
+            enrollment.get_student(),
+            CourseError::InvalidRefundAccounts
+        );
+        require_keys_eq!(
+            wallet.key(),
+            student.get_wallet(),
+            CourseError::InvalidRefundAccounts
+        );
+
+        let shared = &ctx.accounts.escrow;
+        let mut escrow = TuitionEscrow {
+            escrow_mint: shared.escrow_mint.clone(),
+            escrow_token_account: None,
+            refund_token_account: None,
+            treasury_token_account: shared.treasury_token_account.clone(),
+            token_program: shared.token_program.clone(),
+        };
+        if enrollment.get_escrow_mint().is_some() {
+            let (Some(escrow_token_account), Some(refund_token_account)) =
+                (remaining.next(), remaining.next())
+            else {
+                return err!(ErrorCode::AccountNotEnoughKeys);
+            };
+            // The escrow token account is already closed once the tuition was released.
+            if enrollment.get_escrowed() > 0 {
+                escrow.escrow_token_account =
+                    Some(Box::new(Account::try_from(escrow_token_account)?));
+            }
+            escrow.refund_token_account = Some(Box::new(Account::try_from(refund_token_account)?));
+        }
+
+        if !escrow.can_refund_in_full(&enrollment, &ctx.accounts.treasury)? {
+            emit!(CourseRefundSkipped {
+                course: course.key(),
+                enrollment: enrollment.key(),
+            });
+            continue;
+        }
+        escrow.refund_in_full(
+            &ctx.accounts.school,
+            &mut enrollment,
+            &mut ctx.accounts.treasury,
+            wallet,
+        )?;
+        enrollment.close(wallet.clone())?;
+        course.decrement_enrolled_students_count()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,435 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::{
+    associated_token::{create_idempotent, get_associated_token_address, AssociatedToken, Create},
+    token::{transfer_checked, Mint, Token, TokenAccount, TransferChecked},
+};
+
+use super::{
+    completion::require_prerequisites, payment_plan::require_good_standing,
+    scholarship::apply_scholarship, treasury::*,
+};
+use crate::{
+    constants::{StaffRole, MAX_WAITLIST_LENGTH},
+    error::{
+        CourseError, ScholarshipError, SchoolError, StudentError, TreasuryError, WaitlistError,
+    },
+    Course, CourseEnrollment, CourseEnrollmentTrait, CourseTrait, PaymentPlan, PaymentPlanTrait,
+    Scholarship, ScholarshipTrait, School, SchoolTrait, StaffRegistry, StaffRegistryTrait, Student,
+    StudentTrait, Waitlist, WaitlistEntry, WaitlistTrait,
+};
+
+#[derive(Accounts)]
+#[instruction(max_length: u16)]
+pub struct InitWaitlist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub school: Account<'info, School>,
+    #[account(
+        seeds = [b"staff_registry", school.key().as_ref()],
+        bump = staff_registry.get_bump(),
+        constraint = staff_registry.authorizes(&school, &authority.key(), StaffRole::Registrar)
+            @ SchoolError::Unauthorized
+    )]
+    pub staff_registry: Account<'info, StaffRegistry>,
+    #[account(
+        constraint = course.get_school() == school.key() @ CourseError::CourseNotInSchool
+    )]
+    pub course: Account<'info, Course>,
+    #[account(
+        init,
+        payer = authority,
+        space = Waitlist::space(max_length),
+        seeds = [b"waitlist", course.key().as_ref()],
+        bump
+    )]
+    pub waitlist: Account<'info, Waitlist>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Opens a waitlist for a course.
+///
+/// Students can queue until `deadline`; anyone still waiting after it gets their deposit back
+/// through `refund_waitlist`.
+pub fn init_waitlist(ctx: Context<InitWaitlist>, max_length: u16, deadline: i64) -> Result<()> {
+    require!(
+        max_length > 0 && max_length <= MAX_WAITLIST_LENGTH,
+        WaitlistError::InvalidWaitlistLength
+    );
+    require!(
+        deadline > Clock::get()?.unix_timestamp,
+        WaitlistError::InvalidDeadline
+    );
+    ctx.accounts.waitlist.set_inner(Waitlist::new(
+        ctx.accounts.course.key(),
+        max_length,
+        deadline,
+        ctx.bumps.waitlist,
+    ));
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct JoinWaitlist<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    pub school: Account<'info, School>,
+    #[account(
+        seeds = [b"student", school.key().as_ref(), wallet.key().as_ref()],
+        bump = student.get_bump(),
+        constraint = student.has_student_id() @ StudentError::StudentIdNotIssued
+    )]
+    pub student: Account<'info, Student>,
+    #[account(
+        constraint = payment_plan.get_student() == student.key() @ StudentError::PaymentPlanRequired
+    )]
+    pub payment_plan: Option<Account<'info, PaymentPlan>>,
+    #[account(
+        mut,
+        constraint = course.get_school() == school.key() @ CourseError::CourseNotInSchool
+    )]
+    pub course: Account<'info, Course>,
+    #[account(mut, seeds = [b"waitlist", course.key().as_ref()], bump = waitlist.get_bump())]
+    pub waitlist: Account<'info, Waitlist>,
+    /// CHECK: Only checked to be empty, so students already registered cannot queue.
+    #[account(seeds = [b"course_enrollment", course.key().as_ref(), student.key().as_ref()], bump)]
+    pub enrollment: UncheckedAccount<'info>,
+    /// Tuition is escrowed rather than collected, so `payment.treasury_token_account` is unused.
+    pub payment: FeePayment<'info>,
+    /// CHECK: The waitlist's associated token account for the payment mint, created here
+    /// when the school is paid in an SPL token.
+    #[account(mut)]
+    pub escrow_token_account: Option<UncheckedAccount<'info>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    #[account(
+        mut,
+        constraint = scholarship.covers(school.key(), wallet.key(), Some(course.key()))
+            @ ScholarshipError::NotApplicable
+    )]
+    pub scholarship: Option<Account<'info, Scholarship>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Queues the student for a full course and escrows their tuition in the waitlist.
+///
+/// Tuition is escrowed in the school's payment currency: in the waitlist's lamports, or in
+/// its token account for the payment mint. The lamport deposit also covers the rent of the
+/// enrollment record created on promotion (and of its escrow token account for SPL tuition),
+/// so whoever cranks the promotion is reimbursed for it. Prerequisites are checked here
+/// like in `register_for_class`, with the completion records as remaining accounts, and a
+/// scholarship is redeemed against the escrowed tuition the same way.
+pub fn join_waitlist<'info>(ctx: Context<'_, '_, 'info, 'info, JoinWaitlist<'info>>) -> Result<()> {
+    let clock = Clock::get()?;
+    require_good_standing(
+        &ctx.accounts.student,
+        ctx.accounts.payment_plan.as_deref(),
+        clock.unix_timestamp,
+    )?;
+    let course = &mut ctx.accounts.course;
+    let waitlist = &mut ctx.accounts.waitlist;
+    let student = ctx.accounts.student.key();
+
+    require!(!course.is_cancelled(), CourseError::CourseCancelled);
+    require!(course.is_full(), WaitlistError::CourseNotFull);
+    require_prerequisites(course, student, ctx.remaining_accounts)?;
+    require!(
+        clock.unix_timestamp <= waitlist.get_deadline(),
+        WaitlistError::DeadlinePassed
+    );
+    require!(!waitlist.is_full(), WaitlistError::WaitlistFull);
+    require!(
+        !waitlist.contains(&student),
+        WaitlistError::AlreadyWaitlisted
+    );
+    require!(
+        ctx.accounts.enrollment.data_is_empty(),
+        CourseError::AlreadyRegistered
+    );
+
+    let rent = Rent::get()?;
+    let enrollment_rent = rent.minimum_balance(CourseEnrollment::LEN);
+    let tuition = apply_scholarship(ctx.accounts.scholarship.as_mut(), course.get_tution_fee())?;
+    let escrow_mint = ctx.accounts.school.get_payment_mint();
+    let (deposit, escrowed_tokens) = match escrow_mint {
+        None => (
+            tuition
+                .checked_add(enrollment_rent)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+            0,
+        ),
+        Some(payment_mint) => {
+            ctx.accounts.payment.escrow_tokens(
+                payment_mint,
+                &ctx.accounts.wallet,
+                waitlist.to_account_info(),
+                ctx.accounts.escrow_token_account.as_ref(),
+                ctx.accounts.associated_token_program.as_ref(),
+                &ctx.accounts.system_program,
+                tuition,
+            )?;
+            let escrow_rent = rent.minimum_balance(TokenAccount::LEN);
+            (enrollment_rent + escrow_rent, tuition)
+        }
+    };
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.wallet.to_account_info(),
+            to: waitlist.to_account_info(),
+        },
+    );
+    transfer(cpi_context, deposit)?;
+
+    waitlist.push_back(WaitlistEntry {
+        student,
+        wallet: ctx.accounts.wallet.key(),
+        deposit,
+        joined_at: clock.unix_timestamp,
+        escrow_mint,
+        escrowed_tokens,
+    });
+    course.set_waitlist_count(waitlist.len() as u32);
+    Ok(())
+}
+
+/// Token accounts holding the waitlist's escrow for entries paid in an SPL token.
+///
+/// All of them are left out when the entries being processed were paid in lamports.
+#[derive(Accounts)]
+pub struct WaitlistEscrow<'info> {
+    pub escrow_mint: Option<Box<Account<'info, Mint>>>,
+    #[account(mut)]
+    pub waitlist_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+impl<'info> WaitlistEscrow<'info> {
+    /// Moves `amount` of `escrow_mint` out of the waitlist's token account to `to`.
+    pub fn release(
+        &self,
+        waitlist: &Account<'info, Waitlist>,
+        escrow_mint: Pubkey,
+        to: AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        let (Some(mint), Some(from), Some(token_program)) = (
+            &self.escrow_mint,
+            &self.waitlist_token_account,
+            &self.token_program,
+        ) else {
+            return err!(TreasuryError::MissingPaymentAccounts);
+        };
+        require_keys_eq!(mint.key(), escrow_mint, TreasuryError::InvalidPaymentMint);
+        require_keys_eq!(
+            from.key(),
+            get_associated_token_address(&waitlist.key(), &escrow_mint),
+            TreasuryError::InvalidEscrowTokenAccount
+        );
+        let seeds = waitlist.signer_seeds();
+        transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: from.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to,
+                    authority: waitlist.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+            mint.decimals,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct PromoteFromWaitlist<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub school: Account<'info, School>,
+    #[account(
+        mut,
+        constraint = course.get_school() == school.key() @ CourseError::CourseNotInSchool
+    )]
+    pub course: Account<'info, Course>,
+    #[account(mut, seeds = [b"waitlist", course.key().as_ref()], bump = waitlist.get_bump())]
+    pub waitlist: Account<'info, Waitlist>,
+    #[account(
+        constraint = waitlist.front().map(|entry| entry.student) == Some(student.key())
+            @ WaitlistError::NotNextInLine
+    )]
+    pub student: Account<'info, Student>,
+    #[account(
+        init,
+        payer = payer,
+        space = CourseEnrollment::LEN,
+        seeds = [b"course_enrollment", course.key().as_ref(), student.key().as_ref()],
+        bump
+    )]
+    pub enrollment: Account<'info, CourseEnrollment>,
+    pub escrow: WaitlistEscrow<'info>,
+    /// CHECK: The enrollment's associated token account for the entry's mint, created here
+    /// when the tuition was paid in an SPL token.
+    #[account(mut)]
+    pub escrow_token_account: Option<UncheckedAccount<'info>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PromoteFromWaitlist<'info> {
+    /// Opens the enrollment's escrow token account and moves the entry's tokens into it.
+    fn escrow_tokens(&self, escrow_mint: Pubkey, amount: u64) -> Result<()> {
+        let (
+            Some(escrow_token_account),
+            Some(associated_token_program),
+            Some(mint),
+            Some(token_program),
+        ) = (
+            &self.escrow_token_account,
+            &self.associated_token_program,
+            &self.escrow.escrow_mint,
+            &self.escrow.token_program,
+        )
+        else {
+            return err!(TreasuryError::MissingPaymentAccounts);
+        };
+        require_keys_eq!(
+            escrow_token_account.key(),
+            get_associated_token_address(&self.enrollment.key(), &escrow_mint),
+            TreasuryError::InvalidEscrowTokenAccount
+        );
+        create_idempotent(CpiContext::new(
+            associated_token_program.to_account_info(),
+            Create {
+                payer: self.payer.to_account_info(),
+                associated_token: escrow_token_account.to_account_info(),
+                authority: self.enrollment.to_account_info(),
+                mint: mint.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                token_program: token_program.to_account_info(),
+            },
+        ))?;
+        self.escrow.release(
+            &self.waitlist,
+            escrow_mint,
+            escrow_token_account.to_account_info(),
+            amount,
+        )
+    }
+}
+
+/// Gives a freed seat to the student at the front of the waitlist.
+///
+/// Anyone can crank this once a seat is available. The escrowed tuition moves into the new
+/// enrollment record's escrow, in the currency it was paid in, and the payer is reimbursed
+/// the rent of the record and of its escrow token account from the deposit.
+pub fn promote_from_waitlist(ctx: Context<PromoteFromWaitlist>) -> Result<()> {
+    let clock = Clock::get()?;
+    let course = &ctx.accounts.course;
+    let waitlist = &mut ctx.accounts.waitlist;
+
+    require!(!course.is_cancelled(), CourseError::CourseCancelled);
+    require!(!course.is_full(), CourseError::CourseFull);
+    require!(
+        clock.unix_timestamp <= waitlist.get_deadline(),
+        WaitlistError::DeadlinePassed
+    );
+    let entry = waitlist.pop_front().ok_or(WaitlistError::WaitlistEmpty)?;
+
+    let tuition = match entry.escrow_mint {
+        None => {
+            let rent = Rent::get()?.minimum_balance(CourseEnrollment::LEN);
+            let tuition = entry
+                .deposit
+                .checked_sub(rent)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            waitlist.sub_lamports(entry.deposit)?;
+            ctx.accounts.payer.add_lamports(rent)?;
+            ctx.accounts.enrollment.add_lamports(tuition)?;
+            tuition
+        }
+        Some(escrow_mint) => {
+            ctx.accounts
+                .escrow_tokens(escrow_mint, entry.escrowed_tokens)?;
+            ctx.accounts.waitlist.sub_lamports(entry.deposit)?;
+            ctx.accounts.payer.add_lamports(entry.deposit)?;
+            entry.escrowed_tokens
+        }
+    };
+
+    let course = &mut ctx.accounts.course;
+    course.increment_enrolled_students_count()?;
+    course.set_waitlist_count(ctx.accounts.waitlist.len() as u32);
+    ctx.accounts.enrollment.set_inner(CourseEnrollment::new(
+        course.key(),
+        entry.student,
+        tuition,
+        clock.unix_timestamp,
+        entry.escrow_mint,
+        course.get_add_drop_deadline(),
+        course.get_drop_refund_bps(),
+        ctx.bumps.enrollment,
+    ));
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RefundWaitlist<'info> {
+    #[account(mut)]
+    pub course: Account<'info, Course>,
+    #[account(mut, seeds = [b"waitlist", course.key().as_ref()], bump = waitlist.get_bump())]
+    pub waitlist: Account<'info, Waitlist>,
+    /// Shared by the whole batch, whose token entries must all be in `escrow.escrow_mint`.
+    pub escrow: WaitlistEscrow<'info>,
+}
+
+/// Refunds students still waiting once the waitlist deadline has passed or the course
+/// has been cancelled.
+///
+/// The wallets of the entries at the front of the queue are passed, in order, as writable
+/// remaining accounts, so the queue can be drained over several transactions. An entry whose
+/// tuition was paid in an SPL token is followed by the wallet's token account for that mint.
+pub fn refund_waitlist<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RefundWaitlist<'info>>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.course.is_cancelled()
+            || Clock::get()?.unix_timestamp > ctx.accounts.waitlist.get_deadline(),
+        WaitlistError::DeadlineNotReached
+    );
+
+    let mut remaining = ctx.remaining_accounts.iter();
+    while let Some(wallet) = remaining.next() {
+        let entry = ctx
+            .accounts
+            .waitlist
+            .pop_front()
+            .ok_or(WaitlistError::WaitlistEmpty)?;
+        require_keys_eq!(wallet.key(), entry.wallet, WaitlistError::NotNextInLine);
+        if let Some(escrow_mint) = entry.escrow_mint {
+            let Some(refund_token_account) = remaining.next() else {
+                return err!(ErrorCode::AccountNotEnoughKeys);
+            };
+            let refund_token_account = Account::<TokenAccount>::try_from(refund_token_account)?;
+            require_keys_eq!(
+                refund_token_account.owner,
+                entry.wallet,
+                TreasuryError::InvalidRefundTokenAccount
+            );
+            ctx.accounts.escrow.release(
+                &ctx.accounts.waitlist,
+                escrow_mint,
+                refund_token_account.to_account_info(),
+                entry.escrowed_tokens,
+            )?;
+        }
+        ctx.accounts.waitlist.sub_lamports(entry.deposit)?;
+        wallet.add_lamports(entry.deposit)?;
+    }
+    let waitlist_count = ctx.accounts.waitlist.len() as u32;
+    ctx.accounts.course.set_waitlist_count(waitlist_count);
+    Ok(())
+}
@@ -2,9 +2,11 @@ use anchor_lang::prelude::*;
 
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 
+use constants::*;
 use instructions::*;
 use state::*;
 
@@ -15,14 +17,281 @@ pub mod school {
 
     use super::*;
 
+    pub fn update_fee_schedule(
+        ctx: Context<UpdateFeeSchedule>,
+        school_type: SchoolType,
+        tier: FeeTier,
+    ) -> Result<()> {
+        instructions::update_fee_schedule(ctx, school_type, tier)
+    }
+
     pub fn initialize_school(
         ctx: Context<InitializeSchool>,
         enrollment_fee: u64,
         name: String,
         school_type: String,
-        fee_multiplier: u64,
     ) -> Result<()> {
-        instructions::initialize_school(ctx, enrollment_fee, name, school_type, fee_multiplier)
+        instructions::initialize_school(ctx, enrollment_fee, name, school_type)
+    }
+
+    pub fn update_enrollment_fee(ctx: Context<UpdateSchool>, new_fee: u64) -> Result<()> {
+        instructions::update_enrollment_fee(ctx, new_fee)
+    }
+
+    pub fn update_fee_multiplier(
+        ctx: Context<UpdateFeeMultiplier>,
+        new_multiplier_bps: u64,
+    ) -> Result<()> {
+        instructions::update_fee_multiplier(ctx, new_multiplier_bps)
+    }
+
+    pub fn quote_enrollment_fee(ctx: Context<QuoteEnrollmentFee>) -> Result<u64> {
+        instructions::quote_enrollment_fee(ctx)
+    }
+
+    pub fn update_school_name(ctx: Context<UpdateSchoolName>, name: String) -> Result<()> {
+        instructions::update_school_name(ctx, name)
+    }
+
+    pub fn propose_authority(ctx: Context<UpdateSchool>, new_authority: Pubkey) -> Result<()> {
+        instructions::propose_authority(ctx, new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        instructions::accept_authority(ctx)
+    }
+
+    pub fn cancel_authority_transfer(ctx: Context<UpdateSchool>) -> Result<()> {
+        instructions::cancel_authority_transfer(ctx)
+    }
+
+    pub fn grant_staff_role(
+        ctx: Context<UpdateStaff>,
+        member: Pubkey,
+        role: StaffRole,
+    ) -> Result<()> {
+        instructions::grant_staff_role(ctx, member, role)
+    }
+
+    pub fn revoke_staff_role(
+        ctx: Context<UpdateStaff>,
+        member: Pubkey,
+        role: StaffRole,
+    ) -> Result<()> {
+        instructions::revoke_staff_role(ctx, member, role)
+    }
+
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        instructions::create_multisig(ctx, signers, threshold)
+    }
+
+    pub fn create_proposal(ctx: Context<CreateProposal>, action: ProposalAction) -> Result<()> {
+        instructions::create_proposal(ctx, action)
+    }
+
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+        instructions::approve_proposal(ctx)
+    }
+
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        instructions::execute_proposal(ctx)
+    }
+
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        instructions::withdraw_treasury(ctx, amount)
+    }
+
+    pub fn update_payment_mint(ctx: Context<UpdatePaymentMint>) -> Result<()> {
+        instructions::update_payment_mint(ctx)
+    }
+
+    pub fn withdraw_treasury_tokens(
+        ctx: Context<WithdrawTreasuryTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_treasury_tokens(ctx, amount)
+    }
+
+    pub fn issue_scholarship(
+        ctx: Context<IssueScholarship>,
+        wallet: Pubkey,
+        course: Option<Pubkey>,
+        discount: Discount,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::issue_scholarship(ctx, wallet, course, discount, expires_at)
+    }
+
+    pub fn revoke_scholarship(ctx: Context<RevokeScholarship>) -> Result<()> {
+        instructions::revoke_scholarship(ctx)
+    }
+
+    pub fn mint_nft(
+        ctx: Context<InitNFT>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        instructions::mint_nft(ctx, name, symbol, uri)
+    }
+
+    pub fn add_course(
+        ctx: Context<AddCourse>,
+        name: String,
+        symbol: String,
+        uri: String,
+        capacity: u32,
+        tuition_fee: u64,
+    ) -> Result<()> {
+        instructions::add_course(ctx, name, symbol, uri, capacity, tuition_fee)
+    }
+
+    pub fn update_add_drop_policy(
+        ctx: Context<UpdateAddDropPolicy>,
+        add_drop_deadline: i64,
+        drop_refund_bps: u16,
+    ) -> Result<()> {
+        instructions::update_add_drop_policy(ctx, add_drop_deadline, drop_refund_bps)
+    }
+
+    pub fn update_course(
+        ctx: Context<UpdateCourse>,
+        name: Option<String>,
+        capacity: Option<u32>,
+        tuition_fee: Option<u64>,
+    ) -> Result<()> {
+        instructions::update_course(ctx, name, capacity, tuition_fee)
+    }
+
+    pub fn close_course(ctx: Context<CloseCourse>) -> Result<()> {
+        instructions::close_course(ctx)
+    }
+
+    pub fn update_prerequisites<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdatePrerequisites<'info>>,
+        prerequisites: Vec<Pubkey>,
+        min_grade: u8,
+    ) -> Result<()> {
+        instructions::update_prerequisites(ctx, prerequisites, min_grade)
+    }
+
+    pub fn record_completion(ctx: Context<RecordCompletion>, grade: u8) -> Result<()> {
+        instructions::record_completion(ctx, grade)
+    }
+
+    pub fn complete_enrollment(ctx: Context<CompleteEnrollment>) -> Result<()> {
+        instructions::complete_enrollment(ctx)
+    }
+
+    pub fn cancel_course(ctx: Context<CancelCourse>) -> Result<()> {
+        instructions::cancel_course(ctx)
+    }
+
+    pub fn process_course_refunds<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ProcessCourseRefunds<'info>>,
+    ) -> Result<()> {
+        instructions::process_course_refunds(ctx)
+    }
+
+    pub fn add_book(
+        ctx: Context<AddBook>,
+        name: String,
+        symbol: String,
+        uri: String,
+        total_supply: u64,
+    ) -> Result<()> {
+        instructions::add_book(ctx, name, symbol, uri, total_supply)
+    }
+
+    pub fn enroll(ctx: Context<Enroll>, uri: String) -> Result<()> {
+        instructions::enroll(ctx, uri)
+    }
+
+    pub fn request_book(ctx: Context<RequestBook>, edition: u64) -> Result<()> {
+        instructions::request_book(ctx, edition)
+    }
+
+    pub fn enroll_with_payment_plan(
+        ctx: Context<EnrollWithPaymentPlan>,
+        uri: String,
+        installment_count: u8,
+    ) -> Result<()> {
+        instructions::enroll_with_payment_plan(ctx, uri, installment_count)
+    }
+
+    pub fn pay_installment(ctx: Context<PayInstallment>) -> Result<()> {
+        instructions::pay_installment(ctx)
+    }
+
+    pub fn return_book(ctx: Context<ReturnBook>) -> Result<()> {
+        instructions::return_book(ctx)
+    }
+
+    pub fn update_library_config(
+        ctx: Context<UpdateLibraryConfig>,
+        loan_period: i64,
+        late_fee_per_day: u64,
+    ) -> Result<()> {
+        instructions::update_library_config(ctx, loan_period, late_fee_per_day)
+    }
+
+    pub fn reclaim_overdue_book(ctx: Context<ReclaimOverdueBook>) -> Result<()> {
+        instructions::reclaim_overdue_book(ctx)
+    }
+
+    pub fn revoke_student_id(ctx: Context<RevokeStudentId>) -> Result<()> {
+        instructions::revoke_student_id(ctx)
+    }
+
+    pub fn register_for_class<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RegisterForClass<'info>>,
+    ) -> Result<()> {
+        instructions::register_for_class(ctx)
+    }
+
+    pub fn drop_class(ctx: Context<DropClass>) -> Result<()> {
+        instructions::drop_class(ctx)
+    }
+
+    pub fn release_tuition(ctx: Context<ReleaseTuition>) -> Result<()> {
+        instructions::release_tuition(ctx)
+    }
+
+    pub fn init_waitlist(ctx: Context<InitWaitlist>, max_length: u16, deadline: i64) -> Result<()> {
+        instructions::init_waitlist(ctx, max_length, deadline)
+    }
+
+    pub fn join_waitlist<'info>(
+        ctx: Context<'_, '_, 'info, 'info, JoinWaitlist<'info>>,
+    ) -> Result<()> {
+        instructions::join_waitlist(ctx)
+    }
+
+    pub fn promote_from_waitlist(ctx: Context<PromoteFromWaitlist>) -> Result<()> {
+        instructions::promote_from_waitlist(ctx)
+    }
+
+    pub fn refund_waitlist<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundWaitlist<'info>>,
+    ) -> Result<()> {
+        instructions::refund_waitlist(ctx)
+    }
+
+    pub fn update_rate_limit_policy(
+        ctx: Context<UpdateRateLimitPolicy>,
+        mode: RateLimitMode,
+        period: i64,
+        max_mints: u64,
+    ) -> Result<()> {
+        instructions::update_rate_limit_policy(ctx, mode, period, max_mints)
+    }
+
+    pub fn close_rate_limit(ctx: Context<CloseRateLimit>) -> Result<()> {
+        instructions::close_rate_limit(ctx)
     }
 }
 
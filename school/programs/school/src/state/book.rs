@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+
+use crate::error::BookError;
+
+#[account]
+pub struct Book {
+    name: String,
+    school: Pubkey,
+    nft_mint: Pubkey,
+    total_supply: u64,
+    available_copies: u64,
+    bump: u8,
+}
+
+impl Book {
+    /// Account size for a book whose name is `name_len` bytes long, discriminator included.
+    pub fn space(name_len: usize) -> usize {
+        8 + 4 + name_len + 32 + 32 + 8 + 8 + 1
+    }
+}
+
+pub trait BookTrait {
+    fn new(name: String, school: Pubkey, nft_mint: Pubkey, total_supply: u64, bump: u8) -> Self;
+    fn get_name(&self) -> &str;
+    fn get_school(&self) -> Pubkey;
+    fn get_nft_mint(&self) -> Pubkey;
+    fn get_total_supply(&self) -> u64;
+    fn get_available_copies(&self) -> u64;
+    fn get_bump(&self) -> u8;
+    fn checkout_copy(&mut self) -> Result<()>;
+    fn return_copy(&mut self) -> Result<()>;
+}
+
+impl BookTrait for Book {
+    fn new(name: String, school: Pubkey, nft_mint: Pubkey, total_supply: u64, bump: u8) -> Self {
+        Book {
+            name,
+            school,
+            nft_mint,
+            total_supply,
+            available_copies: total_supply,
+            bump,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_school(&self) -> Pubkey {
+        self.school
+    }
+
+    fn get_nft_mint(&self) -> Pubkey {
+        self.nft_mint
+    }
+
+    fn get_total_supply(&self) -> u64 {
+        self.total_supply
+    }
+
+    fn get_available_copies(&self) -> u64 {
+        self.available_copies
+    }
+
+    fn get_bump(&self) -> u8 {
+        self.bump
+    }
+
+    fn checkout_copy(&mut self) -> Result<()> {
+        self.available_copies = self
+            .available_copies
+            .checked_sub(1)
+            .ok_or(BookError::NoCopiesAvailable)?;
+        Ok(())
+    }
+
+    fn return_copy(&mut self) -> Result<()> {
+        require!(
+            self.available_copies < self.total_supply,
+            BookError::AllCopiesReturned
+        );
+        self.available_copies += 1;
+        Ok(())
+    }
+}
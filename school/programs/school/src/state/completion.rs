@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+/// A student's final grade in a course, recorded by an instructor.
+#[account]
+pub struct CompletionRecord {
+    course: Pubkey,
+    student: Pubkey,
+    grade: u8,
+    graded_by: Pubkey,
+    completed_at: i64,
+    bump: u8,
+}
+
+impl CompletionRecord {
+    pub const LEN: usize = 8 + 32 + 32 + 1 + 32 + 8 + 1;
+}
+
+pub trait CompletionRecordTrait {
+    fn new(
+        course: Pubkey,
+        student: Pubkey,
+        grade: u8,
+        graded_by: Pubkey,
+        completed_at: i64,
+        bump: u8,
+    ) -> Self;
+    fn get_course(&self) -> Pubkey;
+    fn get_student(&self) -> Pubkey;
+    fn get_grade(&self) -> u8;
+    fn get_graded_by(&self) -> Pubkey;
+    fn get_completed_at(&self) -> i64;
+    fn get_bump(&self) -> u8;
+}
+
+impl CompletionRecordTrait for CompletionRecord {
+    fn new(
+        course: Pubkey,
+        student: Pubkey,
+        grade: u8,
+        graded_by: Pubkey,
+        completed_at: i64,
+        bump: u8,
+    ) -> Self {
+        CompletionRecord {
+            course,
+            student,
+            grade,
+            graded_by,
+            completed_at,
+            bump,
+        }
+    }
+
+    fn get_course(&self) -> Pubkey {
+        self.course
+    }
+
+    fn get_student(&self) -> Pubkey {
+        self.student
+    }
+
+    fn get_grade(&self) -> u8 {
+        self.grade
+    }
+
+    fn get_graded_by(&self) -> Pubkey {
+        self.graded_by
+    }
+
+    fn get_completed_at(&self) -> i64 {
+        self.completed_at
+    }
+
+    fn get_bump(&self) -> u8 {
+        self.bump
+    }
+}
@@ -1,5 +1,10 @@
 use anchor_lang::prelude::*;
 
+use crate::{
+    constants::{BPS_DENOMINATOR, MAX_GRADE, MAX_PREREQUISITES},
+    error::CourseError,
+};
+
 #[account]
 pub struct Course {
     name: String,
@@ -11,9 +16,40 @@ pub struct Course {
     school: Pubkey,
     authority: Pubkey,
     bump: u8,
+    waitlist_count: u32,
+    add_drop_deadline: i64,
+    drop_refund_bps: u16,
+    cancelled: bool,
+    prerequisites: Vec<Pubkey>,
+    min_grade: u8,
+}
+
+impl Course {
+    /// Account size for a course whose name is `name_len` bytes long and which has
+    /// `prerequisite_count` prerequisites, discriminator included.
+    pub fn space(name_len: usize, prerequisite_count: usize) -> usize {
+        8 + 4
+            + name_len
+            + 32
+            + 32
+            + 4
+            + 4
+            + 8
+            + 32
+            + 32
+            + 1
+            + 4
+            + 8
+            + 2
+            + 1
+            + 4
+            + 32 * prerequisite_count
+            + 1
+    }
 }
 
 pub trait CourseTrait {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         name: String,
         nft_mint: Pubkey,
@@ -44,6 +80,19 @@ pub trait CourseTrait {
     fn set_authority(&mut self, authority: Pubkey);
     fn get_bump(&self) -> u8;
     fn set_bump(&mut self, bump: u8);
+    fn is_full(&self) -> bool;
+    fn increment_enrolled_students_count(&mut self) -> Result<()>;
+    fn decrement_enrolled_students_count(&mut self) -> Result<()>;
+    fn get_waitlist_count(&self) -> u32;
+    fn set_waitlist_count(&mut self, waitlist_count: u32);
+    fn get_add_drop_deadline(&self) -> i64;
+    fn get_drop_refund_bps(&self) -> u16;
+    fn set_add_drop_policy(&mut self, add_drop_deadline: i64, drop_refund_bps: u16) -> Result<()>;
+    fn is_cancelled(&self) -> bool;
+    fn cancel(&mut self);
+    fn get_prerequisites(&self) -> &[Pubkey];
+    fn get_min_grade(&self) -> u8;
+    fn set_prerequisites(&mut self, prerequisites: Vec<Pubkey>, min_grade: u8) -> Result<()>;
 }
 
 impl CourseTrait for Course {
@@ -68,6 +117,12 @@ impl CourseTrait for Course {
             school,
             authority,
             bump,
+            waitlist_count: 0,
+            add_drop_deadline: 0,
+            drop_refund_bps: BPS_DENOMINATOR as u16,
+            cancelled: false,
+            prerequisites: Vec::new(),
+            min_grade: 0,
         }
     }
 
@@ -132,4 +187,77 @@ impl CourseTrait for Course {
     fn set_tution_fee(&mut self, tutuion_fee: u64) {
         self.tutuion_fee = tutuion_fee
     }
+
+    fn is_full(&self) -> bool {
+        self.enrolled_students_count >= self.capacity
+    }
+
+    fn increment_enrolled_students_count(&mut self) -> Result<()> {
+        self.enrolled_students_count = self
+            .enrolled_students_count
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    fn decrement_enrolled_students_count(&mut self) -> Result<()> {
+        self.enrolled_students_count = self
+            .enrolled_students_count
+            .checked_sub(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    fn get_waitlist_count(&self) -> u32 {
+        self.waitlist_count
+    }
+
+    fn set_waitlist_count(&mut self, waitlist_count: u32) {
+        self.waitlist_count = waitlist_count
+    }
+
+    fn get_add_drop_deadline(&self) -> i64 {
+        self.add_drop_deadline
+    }
+
+    fn get_drop_refund_bps(&self) -> u16 {
+        self.drop_refund_bps
+    }
+
+    fn set_add_drop_policy(&mut self, add_drop_deadline: i64, drop_refund_bps: u16) -> Result<()> {
+        require!(
+            u64::from(drop_refund_bps) <= BPS_DENOMINATOR,
+            CourseError::InvalidRefundRate
+        );
+        self.add_drop_deadline = add_drop_deadline;
+        self.drop_refund_bps = drop_refund_bps;
+        Ok(())
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    fn cancel(&mut self) {
+        self.cancelled = true
+    }
+
+    fn get_prerequisites(&self) -> &[Pubkey] {
+        &self.prerequisites
+    }
+
+    fn get_min_grade(&self) -> u8 {
+        self.min_grade
+    }
+
+    fn set_prerequisites(&mut self, prerequisites: Vec<Pubkey>, min_grade: u8) -> Result<()> {
+        require!(
+            prerequisites.len() <= MAX_PREREQUISITES,
+            CourseError::TooManyPrerequisites
+        );
+        require!(min_grade <= MAX_GRADE, CourseError::InvalidGrade);
+        self.prerequisites = prerequisites;
+        self.min_grade = min_grade;
+        Ok(())
+    }
 }
@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::BPS_DENOMINATOR;
+
+/// Registration of a single student in a single course.
+///
+/// The record also escrows the tuition until the course's add/drop deadline: lamports are
+/// held in the account itself and tokens in its associated token account for `escrow_mint`.
+/// The course's add/drop policy is copied in at registration, so later changes to it only
+/// apply to students who register afterwards.
+#[account]
+pub struct CourseEnrollment {
+    course: Pubkey,
+    student: Pubkey,
+    tuition_paid: u64,
+    registered_at: i64,
+    bump: u8,
+    escrowed: u64,
+    escrow_mint: Option<Pubkey>,
+    add_drop_deadline: i64,
+    drop_refund_bps: u16,
+}
+
+impl CourseEnrollment {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 1 + 8 + 33 + 8 + 2;
+
+    pub fn signer_seeds(&self) -> [&[u8]; 4] {
+        [
+            b"course_enrollment",
+            self.course.as_ref(),
+            self.student.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+}
+
+pub trait CourseEnrollmentTrait {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        course: Pubkey,
+        student: Pubkey,
+        tuition_paid: u64,
+        registered_at: i64,
+        escrow_mint: Option<Pubkey>,
+        add_drop_deadline: i64,
+        drop_refund_bps: u16,
+        bump: u8,
+    ) -> Self;
+    fn get_course(&self) -> Pubkey;
+    fn get_student(&self) -> Pubkey;
+    fn get_tuition_paid(&self) -> u64;
+    fn get_registered_at(&self) -> i64;
+    fn get_bump(&self) -> u8;
+    fn get_escrowed(&self) -> u64;
+    fn get_escrow_mint(&self) -> Option<Pubkey>;
+    fn get_add_drop_deadline(&self) -> i64;
+    fn get_drop_refund_bps(&self) -> u16;
+    fn drop_refund(&self, now: i64) -> u64;
+    fn take_escrow(&mut self) -> u64;
+}
+
+impl CourseEnrollmentTrait for CourseEnrollment {
+    fn new(
+        course: Pubkey,
+        student: Pubkey,
+        tuition_paid: u64,
+        registered_at: i64,
+        escrow_mint: Option<Pubkey>,
+        add_drop_deadline: i64,
+        drop_refund_bps: u16,
+        bump: u8,
+    ) -> Self {
+        CourseEnrollment {
+            course,
+            student,
+            tuition_paid,
+            registered_at,
+            bump,
+            escrowed: tuition_paid,
+            escrow_mint,
+            add_drop_deadline,
+            drop_refund_bps,
+        }
+    }
+
+    fn get_course(&self) -> Pubkey {
+        self.course
+    }
+
+    fn get_student(&self) -> Pubkey {
+        self.student
+    }
+
+    fn get_tuition_paid(&self) -> u64 {
+        self.tuition_paid
+    }
+
+    fn get_registered_at(&self) -> i64 {
+        self.registered_at
+    }
+
+    fn get_bump(&self) -> u8 {
+        self.bump
+    }
+
+    fn get_escrowed(&self) -> u64 {
+        self.escrowed
+    }
+
+    fn get_escrow_mint(&self) -> Option<Pubkey> {
+        self.escrow_mint
+    }
+
+    fn get_add_drop_deadline(&self) -> i64 {
+        self.add_drop_deadline
+    }
+
+    fn get_drop_refund_bps(&self) -> u16 {
+        self.drop_refund_bps
+    }
+
+    /// Share of the escrowed tuition returned to a student dropping at `now`; nothing once
+    /// the add/drop deadline has passed.
+    fn drop_refund(&self, now: i64) -> u64 {
+        if now > self.add_drop_deadline {
+            return 0;
+        }
+        // Never more than `escrowed`, since the rate is capped at 100%.
+        (self.escrowed as u128 * self.drop_refund_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+
+    /// Empties the escrow, returning how much was held.
+    fn take_escrow(&mut self) -> u64 {
+        std::mem::take(&mut self.escrowed)
+    }
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::SchoolType, error::SchoolError};
+
+/// Multiplier bounds for one type of school, in basis points.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeTier {
+    pub default_multiplier_bps: u64,
+    pub min_multiplier_bps: u64,
+    pub max_multiplier_bps: u64,
+}
+
+impl FeeTier {
+    pub const LEN: usize = 8 + 8 + 8;
+
+    pub fn is_configured(&self) -> bool {
+        self.min_multiplier_bps > 0
+    }
+
+    pub fn contains(&self, multiplier_bps: u64) -> bool {
+        (self.min_multiplier_bps..=self.max_multiplier_bps).contains(&multiplier_bps)
+    }
+}
+
+/// Program-wide fee multiplier defaults and bounds for each `SchoolType`.
+#[account]
+pub struct FeeSchedule {
+    tiers: [FeeTier; 3],
+    bump: u8,
+}
+
+impl FeeSchedule {
+    pub const LEN: usize = 8 + 3 * FeeTier::LEN + 1;
+}
+
+pub trait FeeScheduleTrait {
+    fn get_tier(&self, school_type: SchoolType) -> Result<FeeTier>;
+    fn get_bump(&self) -> u8;
+    fn set_tier(&mut self, school_type: SchoolType, tier: FeeTier, bump: u8) -> Result<()>;
+}
+
+impl FeeScheduleTrait for FeeSchedule {
+    /// Tier of `school_type`, failing if the schedule does not cover it yet.
+    fn get_tier(&self, school_type: SchoolType) -> Result<FeeTier> {
+        let tier = self.tiers[school_type as usize];
+        require!(tier.is_configured(), SchoolError::FeeScheduleNotConfigured);
+        Ok(tier)
+    }
+
+    fn get_bump(&self) -> u8 {
+        self.bump
+    }
+
+    fn set_tier(&mut self, school_type: SchoolType, tier: FeeTier, bump: u8) -> Result<()> {
+        require!(
+            tier.min_multiplier_bps > 0
+                && tier.min_multiplier_bps <= tier.default_multiplier_bps
+                && tier.default_multiplier_bps <= tier.max_multiplier_bps,
+            SchoolError::InvalidFeeSchedule
+        );
+        self.tiers[school_type as usize] = tier;
+        self.bump = bump;
+        Ok(())
+    }
+}
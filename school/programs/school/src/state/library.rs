@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::SECONDS_PER_DAY, error::BookError};
+
+/// Lending rules shared by every book of a school.
+#[account]
+pub struct LibraryConfig {
+    school: Pubkey,
+    loan_period: i64,
+    late_fee_per_day: u64,
+    bump: u8,
+}
+
+impl LibraryConfig {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 1;
+}
+
+pub trait LibraryConfigTrait {
+    fn get_school(&self) -> Pubkey;
+    fn get_loan_period(&self) -> i64;
+    fn get_late_fee_per_day(&self) -> u64;
+    fn get_bump(&self) -> u8;
+    fn update(
+        &mut self,
+        school: Pubkey,
+        loan_period: i64,
+        late_fee_per_day: u64,
+        bump: u8,
+    ) -> Result<()>;
+    fn calculate_late_fee(&self, due_at: i64, returned_at: i64) -> Result<u64>;
+}
+
+impl LibraryConfigTrait for LibraryConfig {
+    fn get_school(&self) -> Pubkey {
+        self.school
+    }
+
+    fn get_loan_period(&self) -> i64 {
+        self.loan_period
+    }
+
+    fn get_late_fee_per_day(&self) -> u64 {
+        self.late_fee_per_day
+    }
+
+    fn get_bump(&self) -> u8 {
+        self.bump
+    }
+
+    fn update(
+        &mut self,
+        school: Pubkey,
+        loan_period: i64,
+        late_fee_per_day: u64,
+        bump: u8,
+    ) -> Result<()> {
+        require!(loan_period > 0, BookError::InvalidLoanPeriod);
+        self.school = school;
+        self.loan_period = loan_period;
+        self.late_fee_per_day = late_fee_per_day;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Late fee owed for a book returned at `returned_at`, charging every started day after `due_at`.
+    fn calculate_late_fee(&self, due_at: i64, returned_at: i64) -> Result<u64> {
+        if returned_at <= due_at {
+            return Ok(0);
+        }
+        let seconds_late = returned_at.abs_diff(due_at);
+        let days_late = seconds_late.div_ceil(SECONDS_PER_DAY);
+        let fee = days_late
+            .checked_mul(self.late_fee_per_day)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(fee)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = SECONDS_PER_DAY as i64;
+
+    fn config(late_fee_per_day: u64) -> LibraryConfig {
+        LibraryConfig {
+            school: Pubkey::default(),
+            loan_period: 14 * DAY,
+            late_fee_per_day,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn on_time_returns_are_free() {
+        let library = config(1_000);
+        assert_eq!(library.calculate_late_fee(DAY, 0).unwrap(), 0);
+        assert_eq!(library.calculate_late_fee(DAY, DAY).unwrap(), 0);
+    }
+
+    #[test]
+    fn a_started_day_is_charged_in_full() {
+        let library = config(1_000);
+        assert_eq!(library.calculate_late_fee(DAY, DAY + 1).unwrap(), 1_000);
+        assert_eq!(library.calculate_late_fee(DAY, 2 * DAY + 1).unwrap(), 2_000);
+    }
+
+    #[test]
+    fn exact_days_are_not_rounded_up() {
+        let library = config(1_000);
+        assert_eq!(library.calculate_late_fee(DAY, 4 * DAY).unwrap(), 3_000);
+    }
+
+    #[test]
+    fn overflowing_fees_are_rejected() {
+        let library = config(u64::MAX);
+        assert_eq!(library.calculate_late_fee(0, DAY).unwrap(), u64::MAX);
+        assert!(library.calculate_late_fee(0, DAY + 1).is_err());
+        assert!(config(1).calculate_late_fee(i64::MIN, i64::MAX).is_ok());
+    }
+}
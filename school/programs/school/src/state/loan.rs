@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+
+/// A printed edition of a book lent to a student.
+#[account]
+pub struct BookLoan {
+    book: Pubkey,
+    student: Pubkey,
+    wallet: Pubkey,
+    edition_mint: Pubkey,
+    edition: u64,
+    borrowed_at: i64,
+    due_at: i64,
+    bump: u8,
+}
+
+impl BookLoan {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 1;
+}
+
+pub trait BookLoanTrait {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        book: Pubkey,
+        student: Pubkey,
+        wallet: Pubkey,
+        edition_mint: Pubkey,
+        edition: u64,
+        borrowed_at: i64,
+        due_at: i64,
+        bump: u8,
+    ) -> Self;
+    fn get_book(&self) -> Pubkey;
+    fn get_student(&self) -> Pubkey;
+    fn get_wallet(&self) -> Pubkey;
+    fn get_edition_mint(&self) -> Pubkey;
+    fn get_edition(&self) -> u64;
+    fn get_borrowed_at(&self) -> i64;
+    fn get_due_at(&self) -> i64;
+    fn is_overdue(&self, now: i64) -> bool;
+    fn get_bump(&self) -> u8;
+}
+
+impl BookLoanTrait for BookLoan {
+    fn new(
+        book: Pubkey,
+        student: Pubkey,
+        wallet: Pubkey,
+        edition_mint: Pubkey,
+        edition: u64,
+        borrowed_at: i64,
+        due_at: i64,
+        bump: u8,
+    ) -> Self {
+        BookLoan {
+            book,
+            student,
+            wallet,
+            edition_mint,
+            edition,
+            borrowed_at,
+            due_at,
+            bump,
+        }
+    }
+
+    fn get_book(&self) -> Pubkey {
+        self.book
+    }
+
+    fn get_student(&self) -> Pubkey {
+        self.student
+    }
+
+    fn get_wallet(&self) -> Pubkey {
+        self.wallet
+    }
+
+    fn get_edition_mint(&self) -> Pubkey {
+        self.edition_mint
+    }
+
+    fn get_edition(&self) -> u64 {
+        self.edition
+    }
+
+    fn get_borrowed_at(&self) -> i64 {
+        self.borrowed_at
+    }
+
+    fn get_due_at(&self) -> i64 {
+        self.due_at
+    }
+
+    fn is_overdue(&self, now: i64) -> bool {
+        now > self.due_at
+    }
+
+    fn get_bump(&self) -> u8 {
+        self.bump
+    }
+}
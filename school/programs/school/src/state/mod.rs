@@ -1,7 +1,33 @@
+pub mod book;
+pub mod completion;
 pub mod course;
+pub mod enrollment;
+pub mod fee_schedule;
+pub mod library;
+pub mod loan;
+pub mod multisig;
+pub mod payment_plan;
 pub mod ratelimit;
+pub mod scholarship;
 pub mod school;
+pub mod staff;
+pub mod student;
+pub mod treasury;
+pub mod waitlist;
 
+pub use book::*;
+pub use completion::*;
 pub use course::*;
+pub use enrollment::*;
+pub use fee_schedule::*;
+pub use library::*;
+pub use loan::*;
+pub use multisig::*;
+pub use payment_plan::*;
 pub use ratelimit::*;
+pub use scholarship::*;
 pub use school::*;
+pub use staff::*;
+pub use student::*;
+pub use treasury::*;
+pub use waitlist::*;
@@ -0,0 +1,233 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{RateLimitMode, StaffRole, MAX_NAME_LENGTH},
+    error::MultisigError,
+    Discount,
+};
+
+/// M-of-N signer set that holds a school's authority.
+#[account]
+pub struct Multisig {
+    school: Pubkey,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+    proposal_count: u64,
+    bump: u8,
+}
+
+impl Multisig {
+    pub fn space(signer_count: usize) -> usize {
+        8 + 32 + 4 + signer_count * 32 + 1 + 8 + 1
+    }
+}
+
+pub trait MultisigTrait {
+    fn new(school: Pubkey, signers: Vec<Pubkey>, threshold: u8, bump: u8) -> Self;
+    fn get_school(&self) -> Pubkey;
+    fn get_signers(&self) -> &[Pubkey];
+    fn get_threshold(&self) -> u8;
+    fn get_proposal_count(&self) -> u64;
+    fn get_bump(&self) -> u8;
+    fn is_signer(&self, key: &Pubkey) -> bool;
+    fn increment_proposal_count(&mut self) -> Result<()>;
+}
+
+impl MultisigTrait for Multisig {
+    fn new(school: Pubkey, signers: Vec<Pubkey>, threshold: u8, bump: u8) -> Self {
+        Multisig {
+            school,
+            signers,
+            threshold,
+            proposal_count: 0,
+            bump,
+        }
+    }
+
+    fn get_school(&self) -> Pubkey {
+        self.school
+    }
+
+    fn get_signers(&self) -> &[Pubkey] {
+        &self.signers
+    }
+
+    fn get_threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    fn get_proposal_count(&self) -> u64 {
+        self.proposal_count
+    }
+
+    fn get_bump(&self) -> u8 {
+        self.bump
+    }
+
+    fn is_signer(&self, key: &Pubkey) -> bool {
+        self.signers.contains(key)
+    }
+
+    fn increment_proposal_count(&mut self) -> Result<()> {
+        self.proposal_count = self
+            .proposal_count
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+/// Sensitive school change that only takes effect once enough multisig signers approve it.
+///
+/// Once a school is governed by a multisig, every instruction that needs the school
+/// authority's signature has a matching action here.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum ProposalAction {
+    UpdateEnrollmentFee {
+        new_fee: u64,
+    },
+    UpdateFeeMultiplier {
+        new_multiplier: u64,
+    },
+    TransferAuthority {
+        new_authority: Pubkey,
+    },
+    WithdrawTreasury {
+        amount: u64,
+        recipient: Pubkey,
+    },
+    CancelAuthorityTransfer,
+    UpdateSchoolName {
+        name: String,
+    },
+    GrantStaffRole {
+        member: Pubkey,
+        role: StaffRole,
+    },
+    RevokeStaffRole {
+        member: Pubkey,
+        role: StaffRole,
+    },
+    UpdateRateLimitPolicy {
+        mode: RateLimitMode,
+        period: i64,
+        max_mints: u64,
+    },
+    UpdatePaymentMint {
+        payment_mint: Option<Pubkey>,
+    },
+    /// `recipient` is a token account of the mint withdrawn, the current payment mint or
+    /// a previous one.
+    WithdrawTreasuryTokens {
+        amount: u64,
+        recipient: Pubkey,
+    },
+    IssueScholarship {
+        wallet: Pubkey,
+        course: Option<Pubkey>,
+        discount: Discount,
+        expires_at: i64,
+    },
+}
+
+impl ProposalAction {
+    /// Size of the largest variant, with school names at their maximum length.
+    pub const LEN: usize = 1 + 32 + 33 + Discount::LEN + 8;
+
+    /// Rejects actions that would not fit in `LEN` bytes.
+    pub fn validate(&self) -> Result<()> {
+        if let ProposalAction::UpdateSchoolName { name } = self {
+            require!(
+                name.len() <= MAX_NAME_LENGTH,
+                MultisigError::ProposalTooLarge
+            );
+        }
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Proposal {
+    multisig: Pubkey,
+    index: u64,
+    proposer: Pubkey,
+    action: ProposalAction,
+    approvals: Vec<Pubkey>,
+    bump: u8,
+}
+
+impl Proposal {
+    pub fn space(signer_count: usize) -> usize {
+        8 + 32 + 8 + 32 + ProposalAction::LEN + 4 + signer_count * 32 + 1
+    }
+}
+
+pub trait ProposalTrait {
+    fn new(
+        multisig: Pubkey,
+        index: u64,
+        proposer: Pubkey,
+        action: ProposalAction,
+        bump: u8,
+    ) -> Self;
+    fn get_multisig(&self) -> Pubkey;
+    fn get_index(&self) -> u64;
+    fn get_proposer(&self) -> Pubkey;
+    fn get_action(&self) -> &ProposalAction;
+    fn get_approvals(&self) -> &[Pubkey];
+    fn get_bump(&self) -> u8;
+    fn approve(&mut self, signer: Pubkey) -> Result<()>;
+}
+
+impl ProposalTrait for Proposal {
+    /// Creates a proposal already approved by its proposer.
+    fn new(
+        multisig: Pubkey,
+        index: u64,
+        proposer: Pubkey,
+        action: ProposalAction,
+        bump: u8,
+    ) -> Self {
+        Proposal {
+            multisig,
+            index,
+            proposer,
+            action,
+            approvals: vec![proposer],
+            bump,
+        }
+    }
+
+    fn get_multisig(&self) -> Pubkey {
+        self.multisig
+    }
+
+    fn get_index(&self) -> u64 {
+        self.index
+    }
+
+    fn get_proposer(&self) -> Pubkey {
+        self.proposer
+    }
+
+    fn get_action(&self) -> &ProposalAction {
+        &self.action
+    }
+
+    fn get_approvals(&self) -> &[Pubkey] {
+        &self.approvals
+    }
+
+    fn get_bump(&self) -> u8 {
+        self.bump
+    }
+
+    fn approve(&mut self, signer: Pubkey) -> Result<()> {
+        require!(
+            !self.approvals.contains(&signer),
+            MultisigError::AlreadyApproved
+        );
+        self.approvals.push(signer);
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+use anchor_lang::prelude::*;
+
+use crate::error::StudentError;
+
+/// Enrollment fee split into equal installments due every `interval` seconds.
+///
+/// The first installment is paid at enrollment and any remainder of the split is added
+/// to the last one. Installments are paid in `payment_mint`, the school's payment currency
+/// when the plan was opened, even if the school has switched since.
+#[account]
+pub struct PaymentPlan {
+    student: Pubkey,
+    total_fee: u64,
+    installment_count: u8,
+    paid_count: u8,
+    amount_paid: u64,
+    started_at: i64,
+    interval: i64,
+    payment_mint: Option<Pubkey>,
+    bump: u8,
+}
+
+impl PaymentPlan {
+    pub const LEN: usize = 8 + 32 + 8 + 1 + 1 + 8 + 8 + 8 + 33 + 1;
+}
+
+pub trait PaymentPlanTrait {
+    fn new(
+        student: Pubkey,
+        total_fee: u64,
+        installment_count: u8,
+        started_at: i64,
+        interval: i64,
+        payment_mint: Option<Pubkey>,
+        bump: u8,
+    ) -> Self;
+    fn get_student(&self) -> Pubkey;
+    fn get_total_fee(&self) -> u64;
+    fn get_installment_count(&self) -> u8;
+    fn get_paid_count(&self) -> u8;
+    fn get_amount_paid(&self) -> u64;
+    fn get_payment_mint(&self) -> Option<Pubkey>;
+    fn get_bump(&self) -> u8;
+    fn is_completed(&self) -> bool;
+    fn next_installment(&self) -> Result<u64>;
+    fn next_due_at(&self) -> Option<i64>;
+    fn is_delinquent(&self, now: i64, grace_period: i64) -> bool;
+    fn record_payment(&mut self) -> Result<u64>;
+}
+
+impl PaymentPlanTrait for PaymentPlan {
+    fn new(
+        student: Pubkey,
+        total_fee: u64,
+        installment_count: u8,
+        started_at: i64,
+        interval: i64,
+        payment_mint: Option<Pubkey>,
+        bump: u8,
+    ) -> Self {
+        PaymentPlan {
+            student,
+            total_fee,
+            installment_count,
+            paid_count: 0,
+            amount_paid: 0,
+            started_at,
+            interval,
+            payment_mint,
+            bump,
+        }
+    }
+
+    fn get_student(&self) -> Pubkey {
+        self.student
+    }
+
+    fn get_total_fee(&self) -> u64 {
+        self.total_fee
+    }
+
+    fn get_installment_count(&self) -> u8 {
+        self.installment_count
+    }
+
+    fn get_paid_count(&self) -> u8 {
+        self.paid_count
+    }
+
+    fn get_amount_paid(&self) -> u64 {
+        self.amount_paid
+    }
+
+    fn get_payment_mint(&self) -> Option<Pubkey> {
+        self.payment_mint
+    }
+
+    fn get_bump(&self) -> u8 {
+        self.bump
+    }
+
+    fn is_completed(&self) -> bool {
+        self.paid_count >= self.installment_count
+    }
+
+    fn next_installment(&self) -> Result<u64> {
+        require!(!self.is_completed(), StudentError::PaymentPlanCompleted);
+        if self.paid_count + 1 == self.installment_count {
+            return Ok(self.total_fee - self.amount_paid);
+        }
+        Ok(self.total_fee / self.installment_count as u64)
+    }
+
+    /// Due date of the next unpaid installment, `None` once the plan is paid off.
+    fn next_due_at(&self) -> Option<i64> {
+        if self.is_completed() {
+            return None;
+        }
+        self.interval
+            .checked_mul(self.paid_count as i64)
+            .and_then(|offset| self.started_at.checked_add(offset))
+    }
+
+    /// Whether an installment is overdue by more than `grace_period` seconds.
+    fn is_delinquent(&self, now: i64, grace_period: i64) -> bool {
+        self.next_due_at()
+            .is_some_and(|due_at| now > due_at.saturating_add(grace_period))
+    }
+
+    /// Marks the next installment as paid and returns its amount.
+    fn record_payment(&mut self) -> Result<u64> {
+        let amount = self.next_installment()?;
+        self.amount_paid = self
+            .amount_paid
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.paid_count += 1;
+        Ok(amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+
+    fn plan() -> PaymentPlan {
+        PaymentPlan::new(Pubkey::default(), 900, 3, 0, 30 * DAY, None, 255)
+    }
+
+    #[test]
+    fn is_delinquent_only_past_the_grace_period() {
+        let mut plan = plan();
+        plan.record_payment().unwrap();
+        // The second installment is due on day 30.
+        assert!(!plan.is_delinquent(30 * DAY, 7 * DAY));
+        assert!(!plan.is_delinquent(37 * DAY, 7 * DAY));
+        assert!(plan.is_delinquent(37 * DAY + 1, 7 * DAY));
+    }
+
+    #[test]
+    fn paying_the_overdue_installment_lifts_delinquency() {
+        let mut plan = plan();
+        plan.record_payment().unwrap();
+        plan.record_payment().unwrap();
+        assert!(!plan.is_delinquent(40 * DAY, 7 * DAY));
+        assert!(plan.is_delinquent(67 * DAY + 1, 7 * DAY));
+        assert_eq!(plan.record_payment().unwrap(), 300);
+        assert!(!plan.is_delinquent(i64::MAX, 7 * DAY));
+    }
+}
@@ -1,6 +1,214 @@
 use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{RateLimitMode, RATE_LIMIT_COUNT, RATE_LIMIT_PERIOD, RATE_LIMIT_TOKEN_SCALE},
+    error::NFTError,
+};
+
 #[account]
 pub struct RateLimit {
     pub last_mint_time: i64,
     pub mint_count: u64,
+    /// Start of the current window, or the last refill time in `TokenBucket` mode.
+    pub window_start: i64,
+    pub previous_mint_count: u64,
+    pub tokens: u64,
+    pub bump: u8,
+}
+
+impl RateLimit {
+    pub const LEN: usize = 8 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    /// Records a mint at `now`, failing if the policy's quota is exhausted.
+    ///
+    /// - `FixedWindow` counts mints in consecutive windows of `period` seconds.
+    /// - `SlidingWindow` weights the previous window's count by how much of it still
+    ///   overlaps the last `period` seconds, so bursts at a window edge are not doubled.
+    /// - `TokenBucket` refills `max_mints` tokens per `period` continuously and spends one per mint.
+    pub fn consume(&mut self, policy: &RateLimitPolicy, now: i64) -> Result<()> {
+        match policy.mode {
+            RateLimitMode::FixedWindow => self.consume_fixed_window(policy, now)?,
+            RateLimitMode::SlidingWindow => self.consume_sliding_window(policy, now)?,
+            RateLimitMode::TokenBucket => self.consume_token(policy, now)?,
+        }
+        self.last_mint_time = now;
+        Ok(())
+    }
+
+    fn consume_fixed_window(&mut self, policy: &RateLimitPolicy, now: i64) -> Result<()> {
+        if now - self.window_start >= policy.period {
+            self.window_start = now;
+            self.mint_count = 0;
+        }
+        require!(
+            self.mint_count < policy.max_mints,
+            NFTError::RateLimitExceeded
+        );
+        self.mint_count += 1;
+        Ok(())
+    }
+
+    fn consume_sliding_window(&mut self, policy: &RateLimitPolicy, now: i64) -> Result<()> {
+        let elapsed = now - self.window_start;
+        if elapsed >= 2 * policy.period {
+            self.previous_mint_count = 0;
+            self.mint_count = 0;
+            self.window_start = now;
+        } else if elapsed >= policy.period {
+            self.previous_mint_count = self.mint_count;
+            self.mint_count = 0;
+            self.window_start += policy.period;
+        }
+
+        let period = policy.period as u128;
+        let remaining = period - (now - self.window_start) as u128;
+        let weighted =
+            self.previous_mint_count as u128 * remaining + self.mint_count as u128 * period;
+        require!(
+            weighted < policy.max_mints as u128 * period,
+            NFTError::RateLimitExceeded
+        );
+        self.mint_count += 1;
+        Ok(())
+    }
+
+    fn consume_token(&mut self, policy: &RateLimitPolicy, now: i64) -> Result<()> {
+        let capacity = policy.max_mints as u128 * RATE_LIMIT_TOKEN_SCALE;
+        let elapsed = now.saturating_sub(self.window_start).max(0) as u128;
+        // A refill too large to compute fills the bucket anyway.
+        let refill = elapsed
+            .checked_mul(capacity)
+            .map_or(capacity, |refill| refill / policy.period as u128);
+        let tokens = (self.tokens as u128 + refill).min(capacity);
+        require!(
+            tokens >= RATE_LIMIT_TOKEN_SCALE,
+            NFTError::RateLimitExceeded
+        );
+        self.tokens = u64::try_from(tokens - RATE_LIMIT_TOKEN_SCALE)
+            .map_err(|_| ProgramError::ArithmeticOverflow)?;
+        self.window_start = now;
+        self.mint_count += 1;
+        Ok(())
+    }
+}
+
+/// Minting quota applied to every authority that mints under a school.
+#[account]
+pub struct RateLimitPolicy {
+    pub school: Pubkey,
+    pub mode: RateLimitMode,
+    pub period: i64,
+    pub max_mints: u64,
+    pub bump: u8,
+}
+
+impl RateLimitPolicy {
+    pub const LEN: usize = 8 + 32 + 1 + 8 + 8 + 1;
+
+    pub fn is_initialized(&self) -> bool {
+        self.period != 0
+    }
+
+    pub fn set_defaults(&mut self, school: Pubkey, bump: u8) {
+        self.school = school;
+        self.mode = RateLimitMode::FixedWindow;
+        self.period = RATE_LIMIT_PERIOD;
+        self.max_mints = RATE_LIMIT_COUNT;
+        self.bump = bump;
+    }
+
+    pub fn update(&mut self, mode: RateLimitMode, period: i64, max_mints: u64) -> Result<()> {
+        require!(period > 0, NFTError::InvalidRateLimitPolicy);
+        require!(max_mints > 0, NFTError::InvalidRateLimitPolicy);
+        self.mode = mode;
+        self.period = period;
+        self.max_mints = max_mints;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate_limit() -> RateLimit {
+        RateLimit {
+            last_mint_time: 0,
+            mint_count: 0,
+            window_start: 0,
+            previous_mint_count: 0,
+            tokens: 0,
+            bump: 255,
+        }
+    }
+
+    fn policy(mode: RateLimitMode, period: i64, max_mints: u64) -> RateLimitPolicy {
+        RateLimitPolicy {
+            school: Pubkey::default(),
+            mode,
+            period,
+            max_mints,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn sliding_window_weights_the_previous_window() {
+        let policy = policy(RateLimitMode::SlidingWindow, 100, 4);
+        let mut rate_limit = rate_limit();
+        for now in 10..14 {
+            rate_limit.consume(&policy, now).unwrap();
+        }
+        assert!(rate_limit.consume(&policy, 14).is_err());
+
+        // Halfway into the next window, the previous four mints still count for two.
+        rate_limit.consume(&policy, 150).unwrap();
+        rate_limit.consume(&policy, 150).unwrap();
+        assert!(rate_limit.consume(&policy, 150).is_err());
+        // A quarter of the way from its end, they count for one.
+        rate_limit.consume(&policy, 175).unwrap();
+        assert!(rate_limit.consume(&policy, 175).is_err());
+    }
+
+    #[test]
+    fn sliding_window_resets_after_two_full_periods() {
+        let policy = policy(RateLimitMode::SlidingWindow, 100, 2);
+        let mut rate_limit = rate_limit();
+        rate_limit.consume(&policy, 10).unwrap();
+        rate_limit.consume(&policy, 10).unwrap();
+
+        rate_limit.consume(&policy, 210).unwrap();
+        assert_eq!(rate_limit.previous_mint_count, 0);
+        assert_eq!(rate_limit.window_start, 210);
+        rate_limit.consume(&policy, 210).unwrap();
+        assert!(rate_limit.consume(&policy, 210).is_err());
+    }
+
+    #[test]
+    fn token_bucket_refills_continuously() {
+        let policy = policy(RateLimitMode::TokenBucket, 100, 2);
+        let mut rate_limit = rate_limit();
+        // A fresh bucket starts full.
+        rate_limit.consume(&policy, 1_000).unwrap();
+        rate_limit.consume(&policy, 1_000).unwrap();
+        assert!(rate_limit.consume(&policy, 1_000).is_err());
+
+        // Half a period refills one of the two tokens.
+        assert!(rate_limit.consume(&policy, 1_049).is_err());
+        rate_limit.consume(&policy, 1_050).unwrap();
+        assert!(rate_limit.consume(&policy, 1_050).is_err());
+
+        // Refills never exceed the capacity.
+        rate_limit.consume(&policy, 10_000).unwrap();
+        rate_limit.consume(&policy, 10_000).unwrap();
+        assert!(rate_limit.consume(&policy, 10_000).is_err());
+    }
+
+    #[test]
+    fn token_bucket_rejects_capacities_beyond_u64() {
+        let policy = policy(RateLimitMode::TokenBucket, 100, u64::MAX);
+        let mut rate_limit = rate_limit();
+        assert!(rate_limit.consume(&policy, 1_000).is_err());
+        assert_eq!(rate_limit.tokens, 0);
+    }
 }
@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::BPS_DENOMINATOR, error::ScholarshipError};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Discount {
+    /// Share of the fee waived, in basis points.
+    Percentage { bps: u64 },
+    /// Amount waived, in the smallest unit of the school's payment currency.
+    FixedAmount { amount: u64 },
+}
+
+impl Discount {
+    pub const LEN: usize = 1 + 8;
+
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Discount::Percentage { bps } => bps > 0 && bps <= BPS_DENOMINATOR,
+            Discount::FixedAmount { amount } => amount > 0,
+        }
+    }
+
+    /// Part of `fee` this discount waives.
+    pub fn waived(&self, fee: u64) -> u64 {
+        match *self {
+            // bps is at most BPS_DENOMINATOR, so the result fits back into a u64.
+            Discount::Percentage { bps } => {
+                (fee as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64
+            }
+            Discount::FixedAmount { amount } => amount.min(fee),
+        }
+    }
+}
+
+/// Single-use fee waiver granted to a student wallet.
+///
+/// Without a course it applies to the next fee the wallet pays, the enrollment fee or any
+/// course tuition; with a course it only applies to that course's tuition.
+#[account]
+pub struct Scholarship {
+    school: Pubkey,
+    wallet: Pubkey,
+    course: Option<Pubkey>,
+    discount: Discount,
+    expires_at: i64,
+    redeemed: bool,
+    bump: u8,
+}
+
+impl Scholarship {
+    pub const LEN: usize = 8 + 32 + 32 + 33 + Discount::LEN + 8 + 1 + 1;
+}
+
+pub trait ScholarshipTrait {
+    fn new(
+        school: Pubkey,
+        wallet: Pubkey,
+        course: Option<Pubkey>,
+        discount: Discount,
+        expires_at: i64,
+        bump: u8,
+    ) -> Self;
+    fn get_school(&self) -> Pubkey;
+    fn get_wallet(&self) -> Pubkey;
+    fn get_course(&self) -> Option<Pubkey>;
+    fn get_discount(&self) -> Discount;
+    fn get_expires_at(&self) -> i64;
+    fn is_redeemed(&self) -> bool;
+    fn get_bump(&self) -> u8;
+    fn covers(&self, school: Pubkey, wallet: Pubkey, course: Option<Pubkey>) -> bool;
+    fn redeem(&mut self, fee: u64, now: i64) -> Result<u64>;
+}
+
+impl ScholarshipTrait for Scholarship {
+    fn new(
+        school: Pubkey,
+        wallet: Pubkey,
+        course: Option<Pubkey>,
+        discount: Discount,
+        expires_at: i64,
+        bump: u8,
+    ) -> Self {
+        Scholarship {
+            school,
+            wallet,
+            course,
+            discount,
+            expires_at,
+            redeemed: false,
+            bump,
+        }
+    }
+
+    fn get_school(&self) -> Pubkey {
+        self.school
+    }
+
+    fn get_wallet(&self) -> Pubkey {
+        self.wallet
+    }
+
+    fn get_course(&self) -> Option<Pubkey> {
+        self.course
+    }
+
+    fn get_discount(&self) -> Discount {
+        self.discount
+    }
+
+    fn get_expires_at(&self) -> i64 {
+        self.expires_at
+    }
+
+    fn is_redeemed(&self) -> bool {
+        self.redeemed
+    }
+
+    fn get_bump(&self) -> u8 {
+        self.bump
+    }
+
+    /// Whether the scholarship can pay towards a fee of `school` charged to `wallet`,
+    /// for `course` tuition or for the enrollment fee when `course` is `None`.
+    fn covers(&self, school: Pubkey, wallet: Pubkey, course: Option<Pubkey>) -> bool {
+        self.school == school
+            && self.wallet == wallet
+            && (self.course.is_none() || self.course == course)
+    }
+
+    /// Marks the scholarship as used and returns what is left to pay of `fee`.
+    fn redeem(&mut self, fee: u64, now: i64) -> Result<u64> {
+        require!(!self.redeemed, ScholarshipError::AlreadyRedeemed);
+        require!(now <= self.expires_at, ScholarshipError::Expired);
+        self.redeemed = true;
+        Ok(fee - self.discount.waived(fee))
+    }
+}
@@ -1,6 +1,10 @@
 use anchor_lang::prelude::*;
 
-use crate::constants::SchoolType;
+use crate::{
+    constants::{SchoolType, BPS_DENOMINATOR},
+    error::SchoolError,
+    FeeTier,
+};
 
 #[account]
 pub struct School {
@@ -11,8 +15,35 @@ pub struct School {
     book_count: u64,
     student_count: u64,
     school_type: SchoolType,
-    fee_multiplier: u64,
+    /// Multiplier applied to the base fee for this type of school, in basis points.
+    fee_multiplier_bps: u64,
     bump: u8,
+    creator: Pubkey,
+    pending_authority: Option<Pubkey>,
+    payment_mint: Option<Pubkey>,
+    /// Index the next course PDA is derived from. Unlike `class_count` it never goes down,
+    /// so closing a course cannot make a new one collide with a live course address.
+    next_course_index: u64,
+}
+
+impl School {
+    /// Account size for a school whose name is `name_len` bytes long, discriminator included.
+    pub fn space(name_len: usize) -> usize {
+        8 + 4 + name_len + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 1 + 32 + 33 + 33 + 8
+    }
+
+    /// Seeds of the school PDA, used when the school signs a CPI as mint or freeze authority.
+    ///
+    /// The PDA is derived from the authority that created the school, which stays fixed
+    /// when the authority is later transferred.
+    pub fn signer_seeds(&self) -> [&[u8]; 4] {
+        [
+            b"school",
+            self.creator.as_ref(),
+            self.school_type.as_str().as_bytes(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
 }
 
 pub trait SchoolTrait {
@@ -30,15 +61,23 @@ pub trait SchoolTrait {
     fn class_count(&self) -> u64;
     fn book_count(&self) -> u64;
     fn student_count(&self) -> u64;
+    fn next_course_index(&self) -> u64;
     fn increment_class_count(&mut self) -> Result<()>;
+    fn decrement_class_count(&mut self) -> Result<()>;
     fn increment_book_count(&mut self) -> Result<()>;
     fn increment_student_count(&mut self) -> Result<()>;
     fn set_enrollment_fee(&mut self, new_fee: u64);
-    fn set_fee_multiplier(&mut self, new_multiplier: u64) -> Result<()>;
+    fn set_fee_multiplier(&mut self, new_multiplier: u64, tier: &FeeTier) -> Result<()>;
     fn set_authority(&mut self, new_authority: Pubkey);
+    fn get_creator(&self) -> Pubkey;
+    fn set_creator(&mut self, creator: Pubkey);
+    fn get_pending_authority(&self) -> Option<Pubkey>;
+    fn set_pending_authority(&mut self, pending_authority: Option<Pubkey>);
     fn set_name(&mut self, name: String);
     fn get_school_type(&self) -> SchoolType;
     fn set_school_type(&mut self, school_type: SchoolType);
+    fn get_payment_mint(&self) -> Option<Pubkey>;
+    fn set_payment_mint(&mut self, payment_mint: Option<Pubkey>);
     fn calculate_enrollment_fees(&self) -> Result<u64>;
     fn get_bump(&self) -> u8;
     fn set_bump(&mut self, bump: u8);
@@ -55,13 +94,17 @@ impl SchoolTrait for School {
         School {
             authority,
             base_enrollment_fee,
-            fee_multiplier,
+            fee_multiplier_bps: fee_multiplier,
             name,
             school_type,
             class_count: 0,
             book_count: 0,
             student_count: 0,
             bump: 0,
+            creator: authority,
+            pending_authority: None,
+            payment_mint: None,
+            next_course_index: 0,
         }
     }
 
@@ -82,7 +125,7 @@ impl SchoolTrait for School {
     }
 
     fn get_fee_multiplier(&self) -> u64 {
-        self.fee_multiplier
+        self.fee_multiplier_bps
     }
 
     fn class_count(&self) -> u64 {
@@ -97,11 +140,28 @@ impl SchoolTrait for School {
         self.student_count
     }
 
+    fn next_course_index(&self) -> u64 {
+        self.next_course_index
+    }
+
+    /// Counts a newly added course and moves on to the next course index.
     fn increment_class_count(&mut self) -> Result<()> {
         self.class_count = self
             .class_count
             .checked_add(1)
             .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.next_course_index = self
+            .next_course_index
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    fn decrement_class_count(&mut self) -> Result<()> {
+        self.class_count = self
+            .class_count
+            .checked_sub(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
         Ok(())
     }
 
@@ -129,11 +189,29 @@ impl SchoolTrait for School {
         self.authority = new_authority
     }
 
-    fn set_fee_multiplier(&mut self, new_multiplier: u64) -> Result<()> {
-        if new_multiplier == 0 {
-            return Err(ProgramError::InvalidArgument)?;
-        }
-        self.fee_multiplier = new_multiplier;
+    fn get_creator(&self) -> Pubkey {
+        self.creator
+    }
+
+    fn set_creator(&mut self, creator: Pubkey) {
+        self.creator = creator
+    }
+
+    fn get_pending_authority(&self) -> Option<Pubkey> {
+        self.pending_authority
+    }
+
+    fn set_pending_authority(&mut self, pending_authority: Option<Pubkey>) {
+        self.pending_authority = pending_authority
+    }
+
+    /// Sets the multiplier within the bounds of the school type's fee tier.
+    fn set_fee_multiplier(&mut self, new_multiplier: u64, tier: &FeeTier) -> Result<()> {
+        require!(
+            tier.contains(new_multiplier),
+            SchoolError::FeeMultiplierOutOfRange
+        );
+        self.fee_multiplier_bps = new_multiplier;
         Ok(())
     }
 
@@ -145,17 +223,25 @@ impl SchoolTrait for School {
         self.name = name
     }
 
-    fn calculate_enrollment_fees(&self) -> Result<u64> {
-        let fee_in_lamports = self
-            .base_enrollment_fee
-            .checked_mul(1_000_000)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
+    fn get_payment_mint(&self) -> Option<Pubkey> {
+        self.payment_mint
+    }
 
-        let result = fee_in_lamports
-            .checked_mul(self.fee_multiplier)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
+    fn set_payment_mint(&mut self, payment_mint: Option<Pubkey>) {
+        self.payment_mint = payment_mint
+    }
+
+    /// Enrollment fee in the smallest unit of the payment currency (lamports or mint base units).
+    ///
+    /// `base_enrollment_fee` is already in that unit and is scaled by the multiplier in basis
+    /// points, rounding down.
+    fn calculate_enrollment_fees(&self) -> Result<u64> {
+        let fee = (self.base_enrollment_fee as u128)
+            .checked_mul(self.fee_multiplier_bps as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / BPS_DENOMINATOR as u128;
 
-        Ok(result)
+        Ok(u64::try_from(fee).map_err(|_| ProgramError::ArithmeticOverflow)?)
     }
 
     fn get_bump(&self) -> u8 {
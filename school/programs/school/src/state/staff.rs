@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{StaffRole, MAX_STAFF_MEMBERS},
+    error::StaffError,
+    School, SchoolTrait,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct StaffMember {
+    pub member: Pubkey,
+    /// Bitmask of `StaffRole::mask` values.
+    pub roles: u8,
+}
+
+impl StaffMember {
+    pub const LEN: usize = 32 + 1;
+}
+
+/// Staff of a school and the roles each of them holds.
+///
+/// Created together with the school. The school authority implicitly holds every role.
+#[account]
+pub struct StaffRegistry {
+    school: Pubkey,
+    members: Vec<StaffMember>,
+    bump: u8,
+}
+
+impl StaffRegistry {
+    pub const LEN: usize = 8 + 32 + 4 + MAX_STAFF_MEMBERS as usize * StaffMember::LEN + 1;
+}
+
+pub trait StaffRegistryTrait {
+    fn new(school: Pubkey, bump: u8) -> Self;
+    fn get_school(&self) -> Pubkey;
+    fn get_bump(&self) -> u8;
+    fn get_roles(&self, member: &Pubkey) -> u8;
+    fn has_role(&self, member: &Pubkey, role: StaffRole) -> bool;
+    fn authorizes(&self, school: &School, signer: &Pubkey, role: StaffRole) -> bool;
+    fn is_staff(&self, school: &School, signer: &Pubkey) -> bool;
+    fn grant_role(&mut self, member: Pubkey, role: StaffRole) -> Result<()>;
+    fn revoke_role(&mut self, member: &Pubkey, role: StaffRole) -> Result<()>;
+}
+
+impl StaffRegistryTrait for StaffRegistry {
+    fn new(school: Pubkey, bump: u8) -> Self {
+        StaffRegistry {
+            school,
+            members: Vec::new(),
+            bump,
+        }
+    }
+
+    fn get_school(&self) -> Pubkey {
+        self.school
+    }
+
+    fn get_bump(&self) -> u8 {
+        self.bump
+    }
+
+    fn get_roles(&self, member: &Pubkey) -> u8 {
+        self.members
+            .iter()
+            .find(|staff| staff.member == *member)
+            .map_or(0, |staff| staff.roles)
+    }
+
+    fn has_role(&self, member: &Pubkey, role: StaffRole) -> bool {
+        self.get_roles(member) & role.mask() != 0
+    }
+
+    /// Whether `signer` may act in `role`, either as the school authority or as staff.
+    fn authorizes(&self, school: &School, signer: &Pubkey, role: StaffRole) -> bool {
+        school.get_authority() == *signer || self.has_role(signer, role)
+    }
+
+    /// Whether `signer` is the school authority or holds any staff role.
+    fn is_staff(&self, school: &School, signer: &Pubkey) -> bool {
+        school.get_authority() == *signer || self.get_roles(signer) != 0
+    }
+
+    fn grant_role(&mut self, member: Pubkey, role: StaffRole) -> Result<()> {
+        if let Some(staff) = self.members.iter_mut().find(|staff| staff.member == member) {
+            staff.roles |= role.mask();
+            return Ok(());
+        }
+        require!(
+            self.members.len() < MAX_STAFF_MEMBERS as usize,
+            StaffError::StaffRegistryFull
+        );
+        self.members.push(StaffMember {
+            member,
+            roles: role.mask(),
+        });
+        Ok(())
+    }
+
+    /// Removes `role` from `member`, dropping them from the registry once no role is left.
+    fn revoke_role(&mut self, member: &Pubkey, role: StaffRole) -> Result<()> {
+        let index = self
+            .members
+            .iter()
+            .position(|staff| staff.member == *member && staff.roles & role.mask() != 0)
+            .ok_or(StaffError::RoleNotHeld)?;
+        self.members[index].roles &= !role.mask();
+        if self.members[index].roles == 0 {
+            self.members.remove(index);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct Student {
+    school: Pubkey,
+    wallet: Pubkey,
+    student_id: u64,
+    id_mint: Pubkey,
+    enrolled_at: i64,
+    fee_paid: u64,
+    bump: u8,
+    has_payment_plan: bool,
+}
+
+impl Student {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 32 + 8 + 8 + 1 + 1;
+}
+
+pub trait StudentTrait {
+    fn new(
+        school: Pubkey,
+        wallet: Pubkey,
+        student_id: u64,
+        id_mint: Pubkey,
+        enrolled_at: i64,
+        fee_paid: u64,
+        bump: u8,
+    ) -> Self;
+    fn get_school(&self) -> Pubkey;
+    fn get_wallet(&self) -> Pubkey;
+    fn get_student_id(&self) -> u64;
+    fn get_id_mint(&self) -> Pubkey;
+    fn set_id_mint(&mut self, id_mint: Pubkey);
+    fn has_student_id(&self) -> bool;
+    fn get_enrolled_at(&self) -> i64;
+    fn get_fee_paid(&self) -> u64;
+    fn set_fee_paid(&mut self, fee_paid: u64);
+    fn get_bump(&self) -> u8;
+    fn has_payment_plan(&self) -> bool;
+    fn set_has_payment_plan(&mut self, has_payment_plan: bool);
+}
+
+impl StudentTrait for Student {
+    fn new(
+        school: Pubkey,
+        wallet: Pubkey,
+        student_id: u64,
+        id_mint: Pubkey,
+        enrolled_at: i64,
+        fee_paid: u64,
+        bump: u8,
+    ) -> Self {
+        Student {
+            school,
+            wallet,
+            student_id,
+            id_mint,
+            enrolled_at,
+            fee_paid,
+            bump,
+            has_payment_plan: false,
+        }
+    }
+
+    fn get_school(&self) -> Pubkey {
+        self.school
+    }
+
+    fn get_wallet(&self) -> Pubkey {
+        self.wallet
+    }
+
+    fn get_student_id(&self) -> u64 {
+        self.student_id
+    }
+
+    fn get_id_mint(&self) -> Pubkey {
+        self.id_mint
+    }
+
+    fn set_id_mint(&mut self, id_mint: Pubkey) {
+        self.id_mint = id_mint
+    }
+
+    /// Whether the student still holds an ID, which `revoke_student_id` takes away.
+    fn has_student_id(&self) -> bool {
+        self.id_mint != Pubkey::default()
+    }
+
+    fn get_enrolled_at(&self) -> i64 {
+        self.enrolled_at
+    }
+
+    fn get_fee_paid(&self) -> u64 {
+        self.fee_paid
+    }
+
+    fn set_fee_paid(&mut self, fee_paid: u64) {
+        self.fee_paid = fee_paid
+    }
+
+    fn get_bump(&self) -> u8 {
+        self.bump
+    }
+
+    fn has_payment_plan(&self) -> bool {
+        self.has_payment_plan
+    }
+
+    fn set_has_payment_plan(&mut self, has_payment_plan: bool) {
+        self.has_payment_plan = has_payment_plan
+    }
+}
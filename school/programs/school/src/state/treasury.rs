@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+
+/// Lamport vault of a school with running totals for auditing.
+///
+/// Enrollment fees, tuition and late fees are all paid into this account. When the school
+/// accepts an SPL payment mint, fees are paid into the treasury's token account for that mint
+/// instead and counted in the token totals.
+#[account]
+pub struct Treasury {
+    school: Pubkey,
+    total_collected: u64,
+    total_withdrawn: u64,
+    bump: u8,
+    token_collected: u64,
+    token_withdrawn: u64,
+}
+
+impl Treasury {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 1 + 8 + 8;
+
+    pub fn signer_seeds<'a>(&'a self, school: &'a Pubkey) -> [&'a [u8]; 3] {
+        [
+            b"treasury",
+            school.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+}
+
+pub trait TreasuryTrait {
+    fn new(school: Pubkey, bump: u8) -> Self;
+    fn get_school(&self) -> Pubkey;
+    fn get_total_collected(&self) -> u64;
+    fn get_total_withdrawn(&self) -> u64;
+    fn get_bump(&self) -> u8;
+    fn record_deposit(&mut self, amount: u64) -> Result<()>;
+    fn record_withdrawal(&mut self, amount: u64) -> Result<()>;
+    fn get_token_collected(&self) -> u64;
+    fn get_token_withdrawn(&self) -> u64;
+    fn record_token_deposit(&mut self, amount: u64) -> Result<()>;
+    fn record_token_withdrawal(&mut self, amount: u64) -> Result<()>;
+    fn reset_token_totals(&mut self);
+}
+
+impl TreasuryTrait for Treasury {
+    fn new(school: Pubkey, bump: u8) -> Self {
+        Treasury {
+            school,
+            total_collected: 0,
+            total_withdrawn: 0,
+            bump,
+            token_collected: 0,
+            token_withdrawn: 0,
+        }
+    }
+
+    fn get_school(&self) -> Pubkey {
+        self.school
+    }
+
+    fn get_total_collected(&self) -> u64 {
+        self.total_collected
+    }
+
+    fn get_total_withdrawn(&self) -> u64 {
+        self.total_withdrawn
+    }
+
+    fn get_bump(&self) -> u8 {
+        self.bump
+    }
+
+    fn record_deposit(&mut self, amount: u64) -> Result<()> {
+        self.total_collected = self
+            .total_collected
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    fn record_withdrawal(&mut self, amount: u64) -> Result<()> {
+        self.total_withdrawn = self
+            .total_withdrawn
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    fn get_token_collected(&self) -> u64 {
+        self.token_collected
+    }
+
+    fn get_token_withdrawn(&self) -> u64 {
+        self.token_withdrawn
+    }
+
+    fn record_token_deposit(&mut self, amount: u64) -> Result<()> {
+        self.token_collected = self
+            .token_collected
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    fn record_token_withdrawal(&mut self, amount: u64) -> Result<()> {
+        self.token_withdrawn = self
+            .token_withdrawn
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    fn reset_token_totals(&mut self) {
+        self.token_collected = 0;
+        self.token_withdrawn = 0;
+    }
+}
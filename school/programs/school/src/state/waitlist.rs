@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct WaitlistEntry {
+    pub student: Pubkey,
+    pub wallet: Pubkey,
+    /// Lamports held for the entry, tuition included when it was paid in SOL.
+    pub deposit: u64,
+    pub joined_at: i64,
+    /// Mint the tuition was paid in, or `None` for lamports.
+    pub escrow_mint: Option<Pubkey>,
+    /// Tuition held in the waitlist's token account for `escrow_mint`.
+    pub escrowed_tokens: u64,
+}
+
+impl WaitlistEntry {
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 33 + 8;
+}
+
+/// FIFO queue of students waiting for a seat in a full course.
+///
+/// Each entry's deposit (the rent of the enrollment record it will become, plus the tuition
+/// when it is paid in SOL) is escrowed in this account's lamports until the student is
+/// promoted or refunded. Tuition paid in an SPL token is escrowed in this account's
+/// associated token account for that mint instead, and the deposit then also covers the
+/// rent of the enrollment's own escrow token account.
+#[account]
+pub struct Waitlist {
+    course: Pubkey,
+    entries: Vec<WaitlistEntry>,
+    max_length: u16,
+    deadline: i64,
+    bump: u8,
+}
+
+impl Waitlist {
+    pub fn space(max_length: u16) -> usize {
+        8 + 32 + 4 + max_length as usize * WaitlistEntry::LEN + 2 + 8 + 1
+    }
+
+    pub fn signer_seeds(&self) -> [&[u8]; 3] {
+        [
+            b"waitlist",
+            self.course.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+}
+
+pub trait WaitlistTrait {
+    fn new(course: Pubkey, max_length: u16, deadline: i64, bump: u8) -> Self;
+    fn get_course(&self) -> Pubkey;
+    fn get_deadline(&self) -> i64;
+    fn get_bump(&self) -> u8;
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool;
+    fn is_full(&self) -> bool;
+    fn contains(&self, student: &Pubkey) -> bool;
+    fn front(&self) -> Option<&WaitlistEntry>;
+    fn push_back(&mut self, entry: WaitlistEntry);
+    fn pop_front(&mut self) -> Option<WaitlistEntry>;
+}
+
+impl WaitlistTrait for Waitlist {
+    fn new(course: Pubkey, max_length: u16, deadline: i64, bump: u8) -> Self {
+        Waitlist {
+            course,
+            entries: Vec::new(),
+            max_length,
+            deadline,
+            bump,
+        }
+    }
+
+    fn get_course(&self) -> Pubkey {
+        self.course
+    }
+
+    fn get_deadline(&self) -> i64 {
+        self.deadline
+    }
+
+    fn get_bump(&self) -> u8 {
+        self.bump
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn is_full(&self) -> bool {
+        self.entries.len() >= self.max_length as usize
+    }
+
+    fn contains(&self, student: &Pubkey) -> bool {
+        self.entries.iter().any(|entry| entry.student == *student)
+    }
+
+    fn front(&self) -> Option<&WaitlistEntry> {
+        self.entries.first()
+    }
+
+    fn push_back(&mut self, entry: WaitlistEntry) {
+        self.entries.push(entry)
+    }
+
+    fn pop_front(&mut self) -> Option<WaitlistEntry> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        Some(self.entries.remove(0))
+    }
+}
@@ -1,16 +1,2018 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
+import { assert } from "chai";
 import { School } from "../target/types/school";
 
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+);
+
+const findMetadataPda = (mint: PublicKey) =>
+  PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("metadata"),
+      TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+      mint.toBuffer(),
+    ],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+
+const findEditionMarkerPda = (mint: PublicKey, edition: number) =>
+  PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("metadata"),
+      TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+      mint.toBuffer(),
+      Buffer.from("edition"),
+      Buffer.from(Math.floor(edition / 248).toString()),
+    ],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+
+const findMasterEditionPda = (mint: PublicKey) =>
+  PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("metadata"),
+      TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+      mint.toBuffer(),
+      Buffer.from("edition"),
+    ],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+
+const expectError = async (promise: Promise<unknown>, code: string) => {
+  try {
+    await promise;
+  } catch (err) {
+    assert.equal(err.error?.errorCode?.code, code);
+    return;
+  }
+  assert.fail(`expected the transaction to fail with ${code}`);
+};
+
 describe("school", () => {
   // Configure the client to use the local cluster.
-  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
 
   const program = anchor.workspace.School as Program<School>;
+  const authority = provider.wallet.publicKey;
+
+  const [school] = PublicKey.findProgramAddressSync(
+    [Buffer.from("school"), authority.toBuffer(), Buffer.from("University")],
+    program.programId
+  );
+  const [rateLimitPolicy] = PublicKey.findProgramAddressSync(
+    [Buffer.from("rate_limit_policy"), school.toBuffer()],
+    program.programId
+  );
+  const [rateLimit] = PublicKey.findProgramAddressSync(
+    [Buffer.from("rate_limit"), school.toBuffer(), authority.toBuffer()],
+    program.programId
+  );
+
+  const [treasury] = PublicKey.findProgramAddressSync(
+    [Buffer.from("treasury"), school.toBuffer()],
+    program.programId
+  );
+  const [staffRegistry] = PublicKey.findProgramAddressSync(
+    [Buffer.from("staff_registry"), school.toBuffer()],
+    program.programId
+  );
+
+  const fundedKeypair = async () => {
+    const keypair = Keypair.generate();
+    const signature = await provider.connection.requestAirdrop(
+      keypair.publicKey,
+      10 * LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(signature);
+    return keypair;
+  };
+
+  const findStudentPda = (wallet: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("student"), school.toBuffer(), wallet.toBuffer()],
+      program.programId
+    )[0];
+
+  const nftAccounts = (mint: PublicKey) => ({
+    authority,
+    school,
+    staffRegistry,
+    mint,
+    associatedTokenAccount: getAssociatedTokenAddressSync(mint, school, true),
+    metadataAccount: findMetadataPda(mint),
+    masterEditionAccount: findMasterEditionPda(mint),
+  });
+
+  const [programData] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+  );
+  const feeTier = (defaultBps: number, minBps: number, maxBps: number) => ({
+    defaultMultiplierBps: new anchor.BN(defaultBps),
+    minMultiplierBps: new anchor.BN(minBps),
+    maxMultiplierBps: new anchor.BN(maxBps),
+  });
+
+  before(async () => {
+    await program.methods
+      .updateFeeSchedule({ university: {} }, feeTier(30_000, 10_000, 50_000))
+      .accountsPartial({ admin: authority, programData })
+      .rpc();
+    await program.methods
+      .updateFeeSchedule({ college: {} }, feeTier(10_000, 5_000, 20_000))
+      .accountsPartial({ admin: authority, programData })
+      .rpc();
+    await program.methods
+      .initializeSchool(
+        new anchor.BN(2_000_000),
+        "Solana University",
+        "University"
+      )
+      .accountsPartial({ school, authority })
+      .rpc();
+  });
+
+  it("Rejects a school with an empty name", async () => {
+    const [highSchool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("school"), authority.toBuffer(), Buffer.from("HighSchool")],
+      program.programId
+    );
+
+    await expectError(
+      program.methods
+        .initializeSchool(new anchor.BN(1_000_000), "", "HighSchool")
+        .accountsPartial({ school: highSchool, authority })
+        .rpc(),
+      "InvalidNameLength"
+    );
+  });
+
+  it("Mints an NFT with metadata and a master edition", async () => {
+    const mint = Keypair.generate();
+    const accounts = nftAccounts(mint.publicKey);
+
+    await program.methods
+      .mintNft("Blockchain 101", "BC101", "https://example.com/bc101.json")
+      .accountsPartial(accounts)
+      .signers([mint])
+      .rpc();
+
+    const balance = await provider.connection.getTokenAccountBalance(
+      accounts.associatedTokenAccount
+    );
+    assert.equal(balance.value.amount, "1");
+    const token = await getAccount(
+      provider.connection,
+      accounts.associatedTokenAccount
+    );
+    assert.ok(token.owner.equals(school));
+    assert.isNotNull(
+      await provider.connection.getAccountInfo(accounts.metadataAccount)
+    );
+    assert.isNotNull(
+      await provider.connection.getAccountInfo(accounts.masterEditionAccount)
+    );
+  });
+
+  it("Rejects metadata with an insecure URI", async () => {
+    const mint = Keypair.generate();
+
+    await expectError(
+      program.methods
+        .mintNft("Blockchain 101", "BC101", "http://example.com/bc101.json")
+        .accountsPartial(nftAccounts(mint.publicKey))
+        .signers([mint])
+        .rpc(),
+      "InvalidURI"
+    );
+  });
+
+  it("Only lets school staff mint against the school's rate limit policy", async () => {
+    const outsider = await fundedKeypair();
+    const mint = Keypair.generate();
+
+    await expectError(
+      program.methods
+        .mintNft("Blockchain 101", "BC101", "https://example.com/bc101.json")
+        .accountsPartial({
+          ...nftAccounts(mint.publicKey),
+          authority: outsider.publicKey,
+        })
+        .signers([outsider, mint])
+        .rpc(),
+      "Unauthorized"
+    );
+  });
+
+  it("Keeps the rate limit account open while its window is active", async () => {
+    const account = await program.account.rateLimit.fetch(rateLimit);
+    assert.isAtLeast(account.mintCount.toNumber(), 1);
+
+    await expectError(
+      program.methods
+        .closeRateLimit()
+        .accountsPartial({ authority, school })
+        .rpc(),
+      "RateLimitWindowActive"
+    );
+  });
+
+  it("Applies the school's updated rate limit policy", async () => {
+    await program.methods
+      .updateRateLimitPolicy(
+        { tokenBucket: {} },
+        new anchor.BN(60),
+        new anchor.BN(2)
+      )
+      .accountsPartial({ authority, school })
+      .rpc();
+
+    const policy = await program.account.rateLimitPolicy.fetch(
+      rateLimitPolicy
+    );
+    assert.deepEqual(policy.mode, { tokenBucket: {} });
+    assert.equal(policy.period.toNumber(), 60);
+    assert.equal(policy.maxMints.toNumber(), 2);
+
+    await expectError(
+      program.methods
+        .updateRateLimitPolicy(
+          { slidingWindow: {} },
+          new anchor.BN(0),
+          new anchor.BN(2)
+        )
+        .accountsPartial({ authority, school })
+        .rpc(),
+      "InvalidRateLimitPolicy"
+    );
+
+    // Leave enough quota for the remaining tests.
+    await program.methods
+      .updateRateLimitPolicy(
+        { fixedWindow: {} },
+        new anchor.BN(3600),
+        new anchor.BN(100)
+      )
+      .accountsPartial({ authority, school })
+      .rpc();
+  });
+
+  const addCourse = async (name: string, capacity: number, fee: number) => {
+    const { nextCourseIndex } = await program.account.school.fetch(school);
+    const [course] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("course"),
+        school.toBuffer(),
+        nextCourseIndex.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const mint = Keypair.generate();
+    const nft = nftAccounts(mint.publicKey);
+
+    await program.methods
+      .addCourse(
+        name,
+        "COURSE",
+        "https://example.com/course.json",
+        capacity,
+        new anchor.BN(fee)
+      )
+      .accountsPartial({ nft, school, course })
+      .signers([mint])
+      .rpc();
+    return { course, mint: mint.publicKey, nft };
+  };
+
+  const addBook = async (name: string, totalSupply: number) => {
+    const { bookCount } = await program.account.school.fetch(school);
+    const [book] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("book"),
+        school.toBuffer(),
+        bookCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const mint = Keypair.generate();
+    const nft = nftAccounts(mint.publicKey);
+
+    await program.methods
+      .addBook(
+        name,
+        "BOOK",
+        "https://example.com/book.json",
+        new anchor.BN(totalSupply)
+      )
+      .accountsPartial({ nft, school, book })
+      .signers([mint])
+      .rpc();
+    return { book, mint: mint.publicKey, nft };
+  };
+
+  it("Adds a course and mints its NFT", async () => {
+    const { classCount } = await program.account.school.fetch(school);
+    const { course, mint, nft } = await addCourse("Rust 101", 30, 1_000_000);
+
+    const account = await program.account.course.fetch(course);
+    assert.equal(account.name, "Rust 101");
+    assert.equal(account.capacity, 30);
+    assert.ok(account.nftMint.equals(mint));
+    assert.ok(account.nftTokenAccount.equals(nft.associatedTokenAccount));
+    assert.ok(account.authority.equals(school));
+
+    const updated = await program.account.school.fetch(school);
+    assert.equal(updated.classCount.toNumber(), classCount.toNumber() + 1);
+  });
+
+  const enrollAccounts = (wallet: PublicKey) => {
+    const [idMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("student_id"), school.toBuffer(), wallet.toBuffer()],
+      program.programId
+    );
+    return {
+      wallet,
+      school,
+      student: findStudentPda(wallet),
+      treasury,
+      idMint,
+      idTokenAccount: getAssociatedTokenAddressSync(idMint, wallet),
+      idMetadataAccount: findMetadataPda(idMint),
+    };
+  };
+
+  const enroll = (wallet: Keypair) =>
+    program.methods
+      .enroll("https://example.com/student-id.json")
+      .accountsPartial(enrollAccounts(wallet.publicKey))
+      .signers([wallet])
+      .rpc();
+
+  it("Enrolls a student and charges the enrollment fee once", async () => {
+    const wallet = await fundedKeypair();
+    const before = await provider.connection.getBalance(treasury);
+
+    const fee = await program.methods
+      .quoteEnrollmentFee()
+      .accountsPartial({ school })
+      .view();
+    // 2_000_000 lamports base fee at 30_000 bps
+    assert.equal(fee.toNumber(), 6_000_000);
+
+    await enroll(wallet);
+
+    assert.equal(
+      await provider.connection.getBalance(treasury),
+      before + fee.toNumber()
+    );
+    const accounts = enrollAccounts(wallet.publicKey);
+    const account = await program.account.student.fetch(accounts.student);
+    assert.ok(account.wallet.equals(wallet.publicKey));
+    assert.ok(account.idMint.equals(accounts.idMint));
+    assert.equal(account.feePaid.toNumber(), fee.toNumber());
+
+    let reenrolled = true;
+    try {
+      await enroll(wallet);
+    } catch {
+      reenrolled = false;
+    }
+    assert.isFalse(reenrolled, "a student cannot enroll twice");
+  });
+
+  it("Issues a frozen student ID that only the school can revoke", async () => {
+    const wallet = await fundedKeypair();
+    await enroll(wallet);
+    const accounts = enrollAccounts(wallet.publicKey);
+
+    const idAccount = await getAccount(
+      provider.connection,
+      accounts.idTokenAccount
+    );
+    assert.equal(idAccount.amount, BigInt(1));
+    assert.isTrue(idAccount.isFrozen);
+
+    await program.methods
+      .revokeStudentId()
+      .accountsPartial({
+        authority,
+        school,
+        student: accounts.student,
+        wallet: wallet.publicKey,
+        idMint: accounts.idMint,
+        idTokenAccount: accounts.idTokenAccount,
+      })
+      .rpc();
+
+    assert.isNull(
+      await provider.connection.getAccountInfo(accounts.idTokenAccount)
+    );
+    const student = await program.account.student.fetch(accounts.student);
+    assert.ok(student.idMint.equals(PublicKey.default));
+  });
+
+  const findCourseEnrollmentPda = (course: PublicKey, wallet: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("course_enrollment"),
+        course.toBuffer(),
+        findStudentPda(wallet).toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+  const registerForClass = (wallet: Keypair, course: PublicKey) =>
+    program.methods
+      .registerForClass()
+      .accountsPartial({
+        wallet: wallet.publicKey,
+        school,
+        student: findStudentPda(wallet.publicKey),
+        course,
+        enrollment: findCourseEnrollmentPda(course, wallet.publicKey),
+      })
+      .signers([wallet])
+      .rpc();
+
+  it("Refuses registration once the student ID is revoked", async () => {
+    const { course } = await addCourse("Anchor 150", 5, 500_000);
+    const wallet = await fundedKeypair();
+    await enroll(wallet);
+    const accounts = enrollAccounts(wallet.publicKey);
+
+    await program.methods
+      .revokeStudentId()
+      .accountsPartial({
+        authority,
+        school,
+        student: accounts.student,
+        wallet: wallet.publicKey,
+        idMint: accounts.idMint,
+        idTokenAccount: accounts.idTokenAccount,
+      })
+      .rpc();
+
+    await expectError(registerForClass(wallet, course), "StudentIdNotIssued");
+  });
+
+  it("Registers students for a class until it is full", async () => {
+    const { course } = await addCourse("Anchor 201", 1, 500_000);
+    const first = await fundedKeypair();
+    const second = await fundedKeypair();
+    await enroll(first);
+    await enroll(second);
+
+    const enrollment = findCourseEnrollmentPda(course, first.publicKey);
+    await registerForClass(first, course);
+    const { escrowed } = await program.account.courseEnrollment.fetch(
+      enrollment
+    );
+    assert.equal(escrowed.toNumber(), 500_000);
+    const account = await program.account.course.fetch(course);
+    assert.equal(account.enrolledStudentsCount, 1);
+
+    await expectError(registerForClass(second, course), "CourseFull");
+
+    // Without an add/drop window the tuition can be released straight away.
+    const before = await provider.connection.getBalance(treasury);
+    await program.methods
+      .releaseTuition()
+      .accountsPartial({
+        school,
+        course,
+        student: findStudentPda(first.publicKey),
+        wallet: first.publicKey,
+        enrollment,
+        treasury,
+      })
+      .rpc();
+    assert.equal(
+      await provider.connection.getBalance(treasury),
+      before + 500_000
+    );
+  });
+
+  it("Promotes the next waitlisted student when a seat frees up", async () => {
+    const { course } = await addCourse("Solana 301", 1, 250_000);
+    const first = await fundedKeypair();
+    const second = await fundedKeypair();
+    await enroll(first);
+    await enroll(second);
+    await registerForClass(first, course);
+
+    const [waitlist] = PublicKey.findProgramAddressSync(
+      [Buffer.from("waitlist"), course.toBuffer()],
+      program.programId
+    );
+    const deadline = Math.floor(Date.now() / 1000) + 3600;
+    await program.methods
+      .initWaitlist(10, new anchor.BN(deadline))
+      .accountsPartial({ authority, school, course, waitlist })
+      .rpc();
+
+    const secondStudent = findStudentPda(second.publicKey);
+    const secondEnrollment = findCourseEnrollmentPda(course, second.publicKey);
+    await program.methods
+      .joinWaitlist()
+      .accountsPartial({
+        wallet: second.publicKey,
+        school,
+        student: secondStudent,
+        course,
+        waitlist,
+        enrollment: secondEnrollment,
+      })
+      .signers([second])
+      .rpc();
+    await expectError(registerForClass(second, course), "CourseFull");
+
+    await program.methods
+      .dropClass()
+      .accountsPartial({
+        wallet: first.publicKey,
+        school,
+        student: findStudentPda(first.publicKey),
+        course,
+        enrollment: findCourseEnrollmentPda(course, first.publicKey),
+      })
+      .signers([first])
+      .rpc();
+
+    await program.methods
+      .promoteFromWaitlist()
+      .accountsPartial({
+        payer: authority,
+        school,
+        course,
+        waitlist,
+        student: secondStudent,
+        enrollment: secondEnrollment,
+      })
+      .rpc();
+
+    const account = await program.account.course.fetch(course);
+    assert.equal(account.enrolledStudentsCount, 1);
+    assert.equal(account.waitlistCount, 0);
+    const enrollment = await program.account.courseEnrollment.fetch(
+      secondEnrollment
+    );
+    assert.equal(enrollment.tuitionPaid.toNumber(), 250_000);
+  });
+
+  it("Adds a book with all copies available", async () => {
+    const { bookCount } = await program.account.school.fetch(school);
+    const { book, mint } = await addBook("The Rust Book", 3);
+
+    const account = await program.account.book.fetch(book);
+    assert.equal(account.name, "The Rust Book");
+    assert.ok(account.nftMint.equals(mint));
+    assert.equal(account.totalSupply.toNumber(), 3);
+    assert.equal(account.availableCopies.toNumber(), 3);
+
+    const updated = await program.account.school.fetch(school);
+    assert.equal(updated.bookCount.toNumber(), bookCount.toNumber() + 1);
+  });
+
+  const [libraryConfig] = PublicKey.findProgramAddressSync(
+    [Buffer.from("library_config"), school.toBuffer()],
+    program.programId
+  );
+
+  const lendingAccounts = (
+    book: PublicKey,
+    masterMint: PublicKey,
+    editionMint: PublicKey,
+    wallet: PublicKey,
+    edition: number
+  ) => ({
+    school,
+    book,
+    libraryConfig,
+    masterMint,
+    masterTokenAccount: getAssociatedTokenAddressSync(masterMint, school, true),
+    masterMetadata: findMetadataPda(masterMint),
+    masterEdition: findMasterEditionPda(masterMint),
+    editionMint,
+    editionTokenAccount: getAssociatedTokenAddressSync(editionMint, wallet),
+    editionMetadata: findMetadataPda(editionMint),
+    editionAccount: findMasterEditionPda(editionMint),
+    editionMarker: findEditionMarkerPda(masterMint, edition),
+    loan: PublicKey.findProgramAddressSync(
+      [Buffer.from("book_loan"), book.toBuffer(), editionMint.toBuffer()],
+      program.programId
+    )[0],
+  });
+
+  it("Lends a printed edition and restores the copy on return", async () => {
+    await program.methods
+      .updateLibraryConfig(new anchor.BN(14 * 86_400), new anchor.BN(10_000))
+      .accountsPartial({ authority, school, libraryConfig })
+      .rpc();
+    const { book, mint } = await addBook("Programming Solana", 2);
+    const wallet = await fundedKeypair();
+    await enroll(wallet);
+    const editionMint = Keypair.generate();
+    const accounts = lendingAccounts(
+      book,
+      mint,
+      editionMint.publicKey,
+      wallet.publicKey,
+      1
+    );
+
+    await program.methods
+      .requestBook(new anchor.BN(1))
+      .accountsPartial({
+        ...accounts,
+        authority,
+        wallet: wallet.publicKey,
+        student: findStudentPda(wallet.publicKey),
+      })
+      .signers([wallet, editionMint])
+      .rpc();
+
+    let account = await program.account.book.fetch(book);
+    assert.equal(account.availableCopies.toNumber(), 1);
+    const loan = await program.account.bookLoan.fetch(accounts.loan);
+    assert.equal(
+      loan.dueAt.toNumber() - loan.borrowedAt.toNumber(),
+      14 * 86_400
+    );
+    const token = await getAccount(
+      provider.connection,
+      accounts.editionTokenAccount
+    );
+    assert.equal(token.amount, BigInt(1));
+    assert.isTrue(token.isFrozen);
+    assert.ok(token.delegate.equals(school));
+
+    const { masterMetadata, ...returnAccounts } = accounts;
+    await program.methods
+      .returnBook()
+      .accountsPartial({
+        ...returnAccounts,
+        wallet: wallet.publicKey,
+        treasury,
+      })
+      .signers([wallet])
+      .rpc();
+
+    account = await program.account.book.fetch(book);
+    assert.equal(account.availableCopies.toNumber(), 2);
+    assert.isNull(await provider.connection.getAccountInfo(accounts.loan));
+  });
+
+  it("Refuses to reclaim a book before it is overdue", async () => {
+    const { book, mint } = await addBook("Rust for Rustaceans", 1);
+    const wallet = await fundedKeypair();
+    await enroll(wallet);
+    const editionMint = Keypair.generate();
+    const accounts = lendingAccounts(
+      book,
+      mint,
+      editionMint.publicKey,
+      wallet.publicKey,
+      1
+    );
+    await program.methods
+      .requestBook(new anchor.BN(1))
+      .accountsPartial({
+        ...accounts,
+        authority,
+        wallet: wallet.publicKey,
+        student: findStudentPda(wallet.publicKey),
+      })
+      .signers([wallet, editionMint])
+      .rpc();
+
+    const { masterMetadata, libraryConfig, ...reclaimAccounts } = accounts;
+    await expectError(
+      program.methods
+        .reclaimOverdueBook()
+        .accountsPartial({
+          ...reclaimAccounts,
+          authority,
+          wallet: wallet.publicKey,
+          schoolEditionTokenAccount: getAssociatedTokenAddressSync(
+            editionMint.publicKey,
+            school,
+            true
+          ),
+        })
+        .rpc(),
+      "NotOverdue"
+    );
+  });
+
+  it("Lets the authority update the school and resizes it for a longer name", async () => {
+    await program.methods
+      .updateSchoolName("Solana University of Technology")
+      .accountsPartial({ school, authority })
+      .rpc();
+    await program.methods
+      .updateFeeMultiplier(new anchor.BN(40_000))
+      .accountsPartial({ school, authority })
+      .rpc();
+
+    const account = await program.account.school.fetch(school);
+    assert.equal(account.name, "Solana University of Technology");
+    assert.equal(account.feeMultiplierBps.toNumber(), 40_000);
+
+    const outsider = await fundedKeypair();
+    await expectError(
+      program.methods
+        .updateEnrollmentFee(new anchor.BN(0))
+        .accountsPartial({ school, authority: outsider.publicKey })
+        .signers([outsider])
+        .rpc(),
+      "Unauthorized"
+    );
+    await expectError(
+      program.methods
+        .updateFeeMultiplier(new anchor.BN(60_000))
+        .accountsPartial({ school, authority })
+        .rpc(),
+      "FeeMultiplierOutOfRange"
+    );
+
+    await program.methods
+      .updateFeeMultiplier(new anchor.BN(30_000))
+      .accountsPartial({ school, authority })
+      .rpc();
+  });
+
+  it("Hands the school over only once the new authority accepts", async () => {
+    const successor = await fundedKeypair();
+    const outsider = await fundedKeypair();
+
+    await program.methods
+      .proposeAuthority(successor.publicKey)
+      .accountsPartial({ school, authority })
+      .rpc();
+    await expectError(
+      program.methods
+        .acceptAuthority()
+        .accountsPartial({ school, pendingAuthority: outsider.publicKey })
+        .signers([outsider])
+        .rpc(),
+      "NotPendingAuthority"
+    );
+
+    await program.methods
+      .acceptAuthority()
+      .accountsPartial({ school, pendingAuthority: successor.publicKey })
+      .signers([successor])
+      .rpc();
+    let account = await program.account.school.fetch(school);
+    assert.ok(account.authority.equals(successor.publicKey));
+    assert.ok(account.creator.equals(authority));
+    assert.isNull(account.pendingAuthority);
+
+    await expectError(
+      program.methods
+        .updateFeeMultiplier(new anchor.BN(50_000))
+        .accountsPartial({ school, authority })
+        .rpc(),
+      "Unauthorized"
+    );
+
+    await program.methods
+      .proposeAuthority(authority)
+      .accountsPartial({ school, authority: successor.publicKey })
+      .signers([successor])
+      .rpc();
+    await program.methods
+      .acceptAuthority()
+      .accountsPartial({ school, pendingAuthority: authority })
+      .rpc();
+    account = await program.account.school.fetch(school);
+    assert.ok(account.authority.equals(authority));
+  });
+
+  it("Lets staff act within the roles granted to them", async () => {
+    const librarian = await fundedKeypair();
+    const updateLibraryConfig = () =>
+      program.methods
+        .updateLibraryConfig(new anchor.BN(14 * 86_400), new anchor.BN(10_000))
+        .accountsPartial({
+          authority: librarian.publicKey,
+          school,
+          staffRegistry,
+          libraryConfig,
+        })
+        .signers([librarian])
+        .rpc();
+
+    await expectError(updateLibraryConfig(), "Unauthorized");
+
+    await program.methods
+      .grantStaffRole(librarian.publicKey, { librarian: {} })
+      .accountsPartial({ authority, school, staffRegistry })
+      .rpc();
+    await updateLibraryConfig();
+
+    let registry = await program.account.staffRegistry.fetch(staffRegistry);
+    assert.equal(registry.members.length, 1);
+    assert.ok(registry.members[0].member.equals(librarian.publicKey));
+
+    await expectError(
+      program.methods
+        .grantStaffRole(librarian.publicKey, { bursar: {} })
+        .accountsPartial({
+          authority: librarian.publicKey,
+          school,
+          staffRegistry,
+        })
+        .signers([librarian])
+        .rpc(),
+      "Unauthorized"
+    );
+
+    await program.methods
+      .revokeStaffRole(librarian.publicKey, { librarian: {} })
+      .accountsPartial({ authority, school, staffRegistry })
+      .rpc();
+    await expectError(updateLibraryConfig(), "Unauthorized");
+
+    registry = await program.account.staffRegistry.fetch(staffRegistry);
+    assert.equal(registry.members.length, 0);
+  });
+
+  it("Applies sensitive changes only once enough multisig signers approve", async () => {
+    const [college] = PublicKey.findProgramAddressSync(
+      [Buffer.from("school"), authority.toBuffer(), Buffer.from("College")],
+      program.programId
+    );
+    const [multisig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("multisig"), college.toBuffer()],
+      program.programId
+    );
+    const [proposal] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        multisig.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const dean = await fundedKeypair();
+    const bursar = await fundedKeypair();
+
+    await program.methods
+      .initializeSchool(new anchor.BN(1_000_000), "Solana College", "College")
+      .accountsPartial({ school: college, authority })
+      .rpc();
+    await program.methods
+      .createMultisig([authority, dean.publicKey, bursar.publicKey], 2)
+      .accountsPartial({ authority, school: college, multisig })
+      .rpc();
+
+    await expectError(
+      program.methods
+        .updateEnrollmentFee(new anchor.BN(10))
+        .accountsPartial({ school: college, authority })
+        .rpc(),
+      "Unauthorized"
+    );
+
+    await program.methods
+      .createProposal({ updateEnrollmentFee: { newFee: new anchor.BN(10) } })
+      .accountsPartial({
+        proposer: authority,
+        school: college,
+        multisig,
+        proposal,
+      })
+      .rpc();
+    const execute = () =>
+      program.methods
+        .executeProposal()
+        .accountsPartial({
+          executor: authority,
+          school: college,
+          multisig,
+          proposal,
+          proposer: authority,
+        })
+        .rpc();
+    await expectError(execute(), "NotEnoughApprovals");
+
+    await program.methods
+      .approveProposal()
+      .accountsPartial({ signer: dean.publicKey, multisig, proposal })
+      .signers([dean])
+      .rpc();
+    await execute();
+
+    const account = await program.account.school.fetch(college);
+    assert.equal(account.baseEnrollmentFee.toNumber(), 10);
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+
+    // Changes that used to need the authority's own signature go through proposals too.
+    const passProposal = async (
+      index: number,
+      action: Parameters<typeof program.methods.createProposal>[0],
+      accounts: Record<string, PublicKey> = {}
+    ) => {
+      const [next] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("proposal"),
+          multisig.toBuffer(),
+          new anchor.BN(index).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+      await program.methods
+        .createProposal(action)
+        .accountsPartial({
+          proposer: authority,
+          school: college,
+          multisig,
+          proposal: next,
+        })
+        .rpc();
+      await program.methods
+        .approveProposal()
+        .accountsPartial({ signer: dean.publicKey, multisig, proposal: next })
+        .signers([dean])
+        .rpc();
+      await program.methods
+        .executeProposal()
+        .accountsPartial({
+          executor: authority,
+          school: college,
+          multisig,
+          proposal: next,
+          proposer: authority,
+          ...accounts,
+        })
+        .rpc();
+    };
+    const [collegeStaffRegistry] = PublicKey.findProgramAddressSync(
+      [Buffer.from("staff_registry"), college.toBuffer()],
+      program.programId
+    );
+
+    await passProposal(
+      1,
+      { grantStaffRole: { member: bursar.publicKey, role: { registrar: {} } } },
+      { staffRegistry: collegeStaffRegistry }
+    );
+    const registry = await program.account.staffRegistry.fetch(
+      collegeStaffRegistry
+    );
+    assert.ok(registry.members[0].member.equals(bursar.publicKey));
+
+    await passProposal(2, {
+      updateSchoolName: { name: "Solana College of Engineering" },
+    });
+    const renamed = await program.account.school.fetch(college);
+    assert.equal(renamed.name, "Solana College of Engineering");
+
+    const [scholarship] = PublicKey.findProgramAddressSync(
+      [Buffer.from("scholarship"), college.toBuffer(), dean.publicKey.toBuffer()],
+      program.programId
+    );
+    await passProposal(
+      3,
+      {
+        issueScholarship: {
+          wallet: dean.publicKey,
+          course: null,
+          discount: { percentage: { bps: new anchor.BN(2_500) } },
+          expiresAt: new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+        },
+      },
+      { scholarship }
+    );
+    const granted = await program.account.scholarship.fetch(scholarship);
+    assert.ok(granted.school.equals(college));
+    assert.isFalse(granted.redeemed);
+  });
+
+  it("Tracks collected fees and lets the authority withdraw them", async () => {
+    const recipient = Keypair.generate().publicKey;
+    const outsider = await fundedKeypair();
+    const withdraw = (amount: number, signer?: Keypair) =>
+      program.methods
+        .withdrawTreasury(new anchor.BN(amount))
+        .accountsPartial({
+          authority: signer ? signer.publicKey : authority,
+          school,
+          treasury,
+          recipient,
+        })
+        .signers(signer ? [signer] : [])
+        .rpc();
+
+    const before = await program.account.treasury.fetch(treasury);
+    assert.isAbove(before.totalCollected.toNumber(), 0);
+
+    await expectError(withdraw(1_000_000, outsider), "Unauthorized");
+    // Only the school authority moves treasury funds, not its bursars.
+    await program.methods
+      .grantStaffRole(outsider.publicKey, { bursar: {} })
+      .accountsPartial({ authority, school, staffRegistry })
+      .rpc();
+    await expectError(withdraw(1_000_000, outsider), "Unauthorized");
+    await program.methods
+      .revokeStaffRole(outsider.publicKey, { bursar: {} })
+      .accountsPartial({ authority, school, staffRegistry })
+      .rpc();
+    await expectError(
+      withdraw(before.totalCollected.toNumber() + 1),
+      "InsufficientFunds"
+    );
+
+    await withdraw(1_000_000);
+    const after = await program.account.treasury.fetch(treasury);
+    assert.equal(
+      after.totalWithdrawn.toNumber(),
+      before.totalWithdrawn.toNumber() + 1_000_000
+    );
+    assert.equal(await provider.connection.getBalance(recipient), 1_000_000);
+  });
+
+  it("Charges enrollment fees in the school's SPL payment mint", async () => {
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    const usdc = await createMint(provider.connection, payer, authority, null, 6);
+    const treasuryTokenAccount = getAssociatedTokenAddressSync(
+      usdc,
+      treasury,
+      true
+    );
+    await program.methods
+      .updatePaymentMint()
+      .accountsPartial({
+        authority,
+        school,
+        treasury,
+        paymentMint: usdc,
+        treasuryTokenAccount,
+      })
+      .rpc();
+
+    const wallet = await fundedKeypair();
+    const walletTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      usdc,
+      wallet.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      payer,
+      usdc,
+      walletTokenAccount.address,
+      authority,
+      100_000_000
+    );
+    await program.methods
+      .enroll("https://example.com/student-id.json")
+      .accountsPartial({
+        ...enrollAccounts(wallet.publicKey),
+        payment: {
+          paymentMint: usdc,
+          payerTokenAccount: walletTokenAccount.address,
+          treasuryTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+      })
+      .signers([wallet])
+      .rpc();
+
+    // The base fee is now read in the mint's base units.
+    const fee = 6_000_000;
+    const { amount } = await getAccount(
+      provider.connection,
+      treasuryTokenAccount
+    );
+    assert.equal(Number(amount), fee);
+
+    await expectError(
+      program.methods
+        .updatePaymentMint()
+        .accountsPartial({
+          authority,
+          school,
+          treasury,
+          paymentMint: null,
+          treasuryTokenAccount: null,
+        })
+        .rpc(),
+      "TokenBalanceOutstanding"
+    );
+
+    const recipientTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      usdc,
+      authority
+    );
+    await program.methods
+      .withdrawTreasuryTokens(new anchor.BN(fee))
+      .accountsPartial({
+        authority,
+        school,
+        treasury,
+        paymentMint: usdc,
+        treasuryTokenAccount,
+        recipientTokenAccount: recipientTokenAccount.address,
+      })
+      .rpc();
+    await program.methods
+      .updatePaymentMint()
+      .accountsPartial({
+        authority,
+        school,
+        treasury,
+        paymentMint: null,
+        treasuryTokenAccount: null,
+      })
+      .rpc();
+    const account = await program.account.school.fetch(school);
+    assert.isNull(account.paymentMint);
+  });
+
+  it("Escrows waitlist tuition in the school's SPL payment mint", async () => {
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    const wallet = await fundedKeypair();
+    await enroll(wallet);
+    const { course } = await addCourse("Tokenomics 101", 0, 2_000_000);
+    const [waitlist] = PublicKey.findProgramAddressSync(
+      [Buffer.from("waitlist"), course.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .initWaitlist(10, new anchor.BN(Math.floor(Date.now() / 1000) + 3600))
+      .accountsPartial({ authority, school, course, waitlist })
+      .rpc();
+
+    const usdc = await createMint(provider.connection, payer, authority, null, 6);
+    const treasuryTokenAccount = getAssociatedTokenAddressSync(
+      usdc,
+      treasury,
+      true
+    );
+    await program.methods
+      .updatePaymentMint()
+      .accountsPartial({
+        authority,
+        school,
+        treasury,
+        paymentMint: usdc,
+        treasuryTokenAccount,
+      })
+      .rpc();
+    const walletTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      usdc,
+      wallet.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      payer,
+      usdc,
+      walletTokenAccount.address,
+      authority,
+      2_000_000
+    );
+
+    const waitlistTokenAccount = getAssociatedTokenAddressSync(
+      usdc,
+      waitlist,
+      true
+    );
+    await program.methods
+      .joinWaitlist()
+      .accountsPartial({
+        wallet: wallet.publicKey,
+        school,
+        student: findStudentPda(wallet.publicKey),
+        course,
+        waitlist,
+        enrollment: findCourseEnrollmentPda(course, wallet.publicKey),
+        payment: {
+          paymentMint: usdc,
+          payerTokenAccount: walletTokenAccount.address,
+          treasuryTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+        escrowTokenAccount: waitlistTokenAccount,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([wallet])
+      .rpc();
+    const escrowed = await getAccount(provider.connection, waitlistTokenAccount);
+    assert.equal(Number(escrowed.amount), 2_000_000);
+
+    await program.methods
+      .cancelCourse()
+      .accountsPartial({ authority, school, course })
+      .rpc();
+    await program.methods
+      .refundWaitlist()
+      .accountsPartial({
+        course,
+        waitlist,
+        escrow: {
+          escrowMint: usdc,
+          waitlistTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+      })
+      .remainingAccounts([
+        { pubkey: wallet.publicKey, isSigner: false, isWritable: true },
+        {
+          pubkey: walletTokenAccount.address,
+          isSigner: false,
+          isWritable: true,
+        },
+      ])
+      .rpc();
+    const refunded = await getAccount(
+      provider.connection,
+      walletTokenAccount.address
+    );
+    assert.equal(Number(refunded.amount), 2_000_000);
+    const { waitlistCount } = await program.account.course.fetch(course);
+    assert.equal(waitlistCount, 0);
+
+    // Dropping after the tuition was released no longer needs the closed escrow account.
+    const { course: nextCourse } = await addCourse("Tokenomics 201", 5, 1_000_000);
+    const enrollment = findCourseEnrollmentPda(nextCourse, wallet.publicKey);
+    const escrowTokenAccount = getAssociatedTokenAddressSync(
+      usdc,
+      enrollment,
+      true
+    );
+    await program.methods
+      .registerForClass()
+      .accountsPartial({
+        wallet: wallet.publicKey,
+        school,
+        student: findStudentPda(wallet.publicKey),
+        course: nextCourse,
+        enrollment,
+        payment: {
+          paymentMint: usdc,
+          payerTokenAccount: walletTokenAccount.address,
+          treasuryTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+        escrowTokenAccount,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([wallet])
+      .rpc();
+    await program.methods
+      .releaseTuition()
+      .accountsPartial({
+        school,
+        course: nextCourse,
+        student: findStudentPda(wallet.publicKey),
+        wallet: wallet.publicKey,
+        enrollment,
+        treasury,
+        escrow: {
+          escrowMint: usdc,
+          escrowTokenAccount,
+          treasuryTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+      })
+      .rpc();
+    assert.isNull(await provider.connection.getAccountInfo(escrowTokenAccount));
+    await program.methods
+      .dropClass()
+      .accountsPartial({
+        wallet: wallet.publicKey,
+        school,
+        student: findStudentPda(wallet.publicKey),
+        course: nextCourse,
+        enrollment,
+        treasury,
+      })
+      .signers([wallet])
+      .rpc();
+    assert.isNull(await provider.connection.getAccountInfo(enrollment));
+
+    const recipientTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      usdc,
+      authority
+    );
+    await program.methods
+      .withdrawTreasuryTokens(new anchor.BN(1_000_000))
+      .accountsPartial({
+        authority,
+        school,
+        treasury,
+        paymentMint: usdc,
+        treasuryTokenAccount,
+        recipientTokenAccount: recipientTokenAccount.address,
+      })
+      .rpc();
+    await program.methods
+      .updatePaymentMint()
+      .accountsPartial({
+        authority,
+        school,
+        treasury,
+        paymentMint: null,
+        treasuryTokenAccount: null,
+      })
+      .rpc();
+  });
+
+  it("Waives part of the enrollment fee with a scholarship", async () => {
+    const wallet = await fundedKeypair();
+    const [scholarship] = PublicKey.findProgramAddressSync(
+      [Buffer.from("scholarship"), school.toBuffer(), wallet.publicKey.toBuffer()],
+      program.programId
+    );
+    const expiresAt = Math.floor(Date.now() / 1000) + 3600;
+    await program.methods
+      .issueScholarship(
+        wallet.publicKey,
+        null,
+        { percentage: { bps: new anchor.BN(5_000) } },
+        new anchor.BN(expiresAt)
+      )
+      .accountsPartial({ authority, school, scholarship })
+      .rpc();
+
+    const fee = await program.methods
+      .quoteEnrollmentFee()
+      .accountsPartial({ school })
+      .view();
+    await program.methods
+      .enroll("https://example.com/student-id.json")
+      .accountsPartial({ ...enrollAccounts(wallet.publicKey), scholarship })
+      .signers([wallet])
+      .rpc();
+
+    const student = await program.account.student.fetch(
+      findStudentPda(wallet.publicKey)
+    );
+    assert.equal(student.feePaid.toNumber(), fee.toNumber() / 2);
+    const account = await program.account.scholarship.fetch(scholarship);
+    assert.isTrue(account.redeemed);
+
+    // A school-wide scholarship would also cover tuition, but only once.
+    const { course } = await addCourse("Scholars 101", 5, 1_000_000);
+    await expectError(
+      program.methods
+        .registerForClass()
+        .accountsPartial({
+          wallet: wallet.publicKey,
+          school,
+          student: findStudentPda(wallet.publicKey),
+          course,
+          enrollment: findCourseEnrollmentPda(course, wallet.publicKey),
+          scholarship,
+        })
+        .signers([wallet])
+        .rpc(),
+      "AlreadyRedeemed"
+    );
+
+    const [courseScholarship] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("scholarship"),
+        school.toBuffer(),
+        wallet.publicKey.toBuffer(),
+        course.toBuffer(),
+      ],
+      program.programId
+    );
+    await expectError(
+      program.methods
+        .issueScholarship(
+          wallet.publicKey,
+          course,
+          { fixedAmount: { amount: new anchor.BN(100_000) } },
+          new anchor.BN(expiresAt)
+        )
+        .accountsPartial({ authority, school, scholarship: courseScholarship })
+        .rpc(),
+      "InvalidCourse"
+    );
+
+    await program.methods
+      .revokeScholarship()
+      .accountsPartial({ authority, school, scholarship })
+      .rpc();
+    assert.isNull(await provider.connection.getAccountInfo(scholarship));
+  });
+
+  it("Waives part of the waitlisted tuition with a course scholarship", async () => {
+    const { course } = await addCourse("Scholars 201", 1, 250_000);
+    const first = await fundedKeypair();
+    const second = await fundedKeypair();
+    await enroll(first);
+    await enroll(second);
+    await registerForClass(first, course);
+
+    const [waitlist] = PublicKey.findProgramAddressSync(
+      [Buffer.from("waitlist"), course.toBuffer()],
+      program.programId
+    );
+    const expiresAt = Math.floor(Date.now() / 1000) + 3600;
+    await program.methods
+      .initWaitlist(10, new anchor.BN(expiresAt))
+      .accountsPartial({ authority, school, course, waitlist })
+      .rpc();
+    const [scholarship] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("scholarship"),
+        school.toBuffer(),
+        second.publicKey.toBuffer(),
+        course.toBuffer(),
+      ],
+      program.programId
+    );
+    await program.methods
+      .issueScholarship(
+        second.publicKey,
+        course,
+        { fixedAmount: { amount: new anchor.BN(100_000) } },
+        new anchor.BN(expiresAt)
+      )
+      .accountsPartial({ authority, school, courseAccount: course, scholarship })
+      .rpc();
+
+    const secondStudent = findStudentPda(second.publicKey);
+    const secondEnrollment = findCourseEnrollmentPda(course, second.publicKey);
+    await program.methods
+      .joinWaitlist()
+      .accountsPartial({
+        wallet: second.publicKey,
+        school,
+        student: secondStudent,
+        course,
+        waitlist,
+        enrollment: secondEnrollment,
+        scholarship,
+      })
+      .signers([second])
+      .rpc();
+    const account = await program.account.scholarship.fetch(scholarship);
+    assert.isTrue(account.redeemed);
+
+    await program.methods
+      .dropClass()
+      .accountsPartial({
+        wallet: first.publicKey,
+        school,
+        student: findStudentPda(first.publicKey),
+        course,
+        enrollment: findCourseEnrollmentPda(course, first.publicKey),
+      })
+      .signers([first])
+      .rpc();
+    await program.methods
+      .promoteFromWaitlist()
+      .accountsPartial({
+        payer: authority,
+        school,
+        course,
+        waitlist,
+        student: secondStudent,
+        enrollment: secondEnrollment,
+      })
+      .rpc();
+    const enrollment = await program.account.courseEnrollment.fetch(
+      secondEnrollment
+    );
+    assert.equal(enrollment.tuitionPaid.toNumber(), 150_000);
+  });
+
+  it("Splits the enrollment fee into installments", async () => {
+    const wallet = await fundedKeypair();
+    const student = findStudentPda(wallet.publicKey);
+    const [paymentPlan] = PublicKey.findProgramAddressSync(
+      [Buffer.from("payment_plan"), student.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .enrollWithPaymentPlan("https://example.com/student-id.json", 3)
+      .accountsPartial({
+        enroll: enrollAccounts(wallet.publicKey),
+        paymentPlan,
+      })
+      .signers([wallet])
+      .rpc();
+
+    let plan = await program.account.paymentPlan.fetch(paymentPlan);
+    assert.equal(plan.totalFee.toNumber(), 6_000_000);
+    assert.equal(plan.paidCount, 1);
+    const account = await program.account.student.fetch(student);
+    assert.equal(account.feePaid.toNumber(), 2_000_000);
+    assert.isTrue(account.hasPaymentPlan);
+
+    const before = await provider.connection.getBalance(treasury);
+    await program.methods
+      .payInstallment()
+      .accountsPartial({ wallet: wallet.publicKey, school, treasury })
+      .signers([wallet])
+      .rpc();
+    plan = await program.account.paymentPlan.fetch(paymentPlan);
+    assert.equal(plan.paidCount, 2);
+    assert.equal(plan.amountPaid.toNumber(), 4_000_000);
+    const updated = await program.account.student.fetch(student);
+    assert.equal(updated.feePaid.toNumber(), 4_000_000);
+    assert.equal(
+      await provider.connection.getBalance(treasury),
+      before + 2_000_000
+    );
+
+    // The last installment is still owed in SOL after the school switches mints.
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    const usdc = await createMint(provider.connection, payer, authority, null, 6);
+    const switchMint = (paymentMint: PublicKey | null) =>
+      program.methods
+        .updatePaymentMint()
+        .accountsPartial({
+          authority,
+          school,
+          treasury,
+          paymentMint,
+          treasuryTokenAccount:
+            paymentMint &&
+            getAssociatedTokenAddressSync(paymentMint, treasury, true),
+        })
+        .rpc();
+    await switchMint(usdc);
+    await program.methods
+      .payInstallment()
+      .accountsPartial({ wallet: wallet.publicKey, school, treasury })
+      .signers([wallet])
+      .rpc();
+    await switchMint(null);
+    plan = await program.account.paymentPlan.fetch(paymentPlan);
+    assert.isNull(plan.paymentMint);
+    assert.equal(plan.amountPaid.toNumber(), 6_000_000);
+    assert.equal(
+      await provider.connection.getBalance(treasury),
+      before + 4_000_000
+    );
+  });
+
+  it("Refunds part of the escrowed tuition when dropping before the deadline", async () => {
+    const { course } = await addCourse("Escrow 101", 5, 1_000_000);
+    const deadline = Math.floor(Date.now() / 1000) + 3600;
+    await program.methods
+      .updateAddDropPolicy(new anchor.BN(deadline), 7_500)
+      .accountsPartial({ authority, school, course })
+      .rpc();
+
+    const wallet = await fundedKeypair();
+    await enroll(wallet);
+    const enrollment = findCourseEnrollmentPda(course, wallet.publicKey);
+    await registerForClass(wallet, course);
+
+    const releaseAccounts = {
+      school,
+      course,
+      student: findStudentPda(wallet.publicKey),
+      wallet: wallet.publicKey,
+      enrollment,
+      treasury,
+    };
+    await expectError(
+      program.methods.releaseTuition().accountsPartial(releaseAccounts).rpc(),
+      "AddDropWindowOpen"
+    );
+
+    // Registered students keep the policy they registered under.
+    await program.methods
+      .updateAddDropPolicy(new anchor.BN(deadline - 7200), 0)
+      .accountsPartial({ authority, school, course })
+      .rpc();
+    await expectError(
+      program.methods.releaseTuition().accountsPartial(releaseAccounts).rpc(),
+      "AddDropWindowOpen"
+    );
+
+    const treasuryBefore = await provider.connection.getBalance(treasury);
+    const walletBefore = await provider.connection.getBalance(wallet.publicKey);
+    const held = await provider.connection.getBalance(enrollment);
+    await program.methods
+      .dropClass()
+      .accountsPartial({
+        wallet: wallet.publicKey,
+        school,
+        student: findStudentPda(wallet.publicKey),
+        course,
+        enrollment,
+        treasury,
+      })
+      .signers([wallet])
+      .rpc();
+
+    assert.equal(
+      await provider.connection.getBalance(treasury),
+      treasuryBefore + 250_000
+    );
+    // The closed enrollment returns its rent along with the refunded share.
+    assert.equal(
+      await provider.connection.getBalance(wallet.publicKey),
+      walletBefore + held - 250_000
+    );
+    const account = await program.account.course.fetch(course);
+    assert.equal(account.enrolledStudentsCount, 0);
+
+    await expectError(
+      program.methods
+        .updateAddDropPolicy(new anchor.BN(deadline), 10_001)
+        .accountsPartial({ authority, school, course })
+        .rpc(),
+      "InvalidRefundRate"
+    );
+  });
+
+  it("Refunds every enrolled student when a course is cancelled", async () => {
+    const { course } = await addCourse("Cancelled 101", 5, 400_000);
+    const first = await fundedKeypair();
+    const second = await fundedKeypair();
+    const late = await fundedKeypair();
+    await enroll(first);
+    await enroll(second);
+    await enroll(late);
+    await registerForClass(first, course);
+    await registerForClass(second, course);
+
+    // The first student's tuition has already reached the treasury.
+    const firstEnrollment = findCourseEnrollmentPda(course, first.publicKey);
+    await program.methods
+      .releaseTuition()
+      .accountsPartial({
+        school,
+        course,
+        student: findStudentPda(first.publicKey),
+        wallet: first.publicKey,
+        enrollment: firstEnrollment,
+        treasury,
+      })
+      .rpc();
+
+    await program.methods
+      .cancelCourse()
+      .accountsPartial({ authority, school, course })
+      .rpc();
+    await expectError(registerForClass(late, course), "CourseCancelled");
+
+    const refundAccounts = (wallet: Keypair) => [
+      {
+        pubkey: findCourseEnrollmentPda(course, wallet.publicKey),
+        isSigner: false,
+        isWritable: true,
+      },
+      {
+        pubkey: findStudentPda(wallet.publicKey),
+        isSigner: false,
+        isWritable: false,
+      },
+      { pubkey: wallet.publicKey, isSigner: false, isWritable: true },
+    ];
+    const balances = async () =>
+      Promise.all(
+        [first, second].map(({ publicKey }) =>
+          provider.connection.getBalance(publicKey)
+        )
+      );
+
+    // While the treasury cannot pay back the released tuition, the refund is reported
+    // and skipped instead of failing the batch.
+    const available =
+      (await provider.connection.getBalance(treasury)) -
+      (await provider.connection.getMinimumBalanceForRentExemption(
+        program.account.treasury.size
+      ));
+    await program.methods
+      .withdrawTreasury(new anchor.BN(available))
+      .accountsPartial({ authority, school, treasury, recipient: authority })
+      .rpc();
+    await program.methods
+      .processCourseRefunds()
+      .accountsPartial({ school, course, treasury })
+      .remainingAccounts(refundAccounts(first))
+      .rpc();
+    assert.isNotNull(await provider.connection.getAccountInfo(firstEnrollment));
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: authority,
+          toPubkey: treasury,
+          lamports: available,
+        })
+      )
+    );
+
+    const before = await balances();
+    const rent = await provider.connection.getBalance(firstEnrollment);
+
+    // One enrollment per transaction, as a crank for a large course would.
+    for (const wallet of [first, second]) {
+      await program.methods
+        .processCourseRefunds()
+        .accountsPartial({ school, course, treasury })
+        .remainingAccounts(refundAccounts(wallet))
+        .rpc();
+    }
+
+    const after = await balances();
+    assert.equal(after[0], before[0] + rent + 400_000);
+    assert.equal(after[1], before[1] + rent + 400_000);
+    assert.isNull(await provider.connection.getAccountInfo(firstEnrollment));
+    const account = await program.account.course.fetch(course);
+    assert.isTrue(account.cancelled);
+    assert.equal(account.enrolledStudentsCount, 0);
+  });
+
+  it("Updates a course and closes it once nobody is enrolled", async () => {
+    const { course, mint } = await addCourse("Draft 101", 2, 100_000);
+    const wallet = await fundedKeypair();
+    await enroll(wallet);
+    await registerForClass(wallet, course);
+
+    await expectError(
+      program.methods
+        .updateCourse(null, 0, null)
+        .accountsPartial({ authority, school, course })
+        .rpc(),
+      "CapacityBelowEnrollment"
+    );
+    await program.methods
+      .updateCourse("Final 101", 1, new anchor.BN(200_000))
+      .accountsPartial({ authority, school, course })
+      .rpc();
+    const account = await program.account.course.fetch(course);
+    assert.equal(account.name, "Final 101");
+    assert.equal(account.capacity, 1);
+    assert.equal(account.tutuionFee.toNumber(), 200_000);
+
+    const [waitlist] = PublicKey.findProgramAddressSync(
+      [Buffer.from("waitlist"), course.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .initWaitlist(5, new anchor.BN(Math.floor(Date.now() / 1000) + 3600))
+      .accountsPartial({ authority, school, course, waitlist })
+      .rpc();
+    const closeAccounts = {
+      authority,
+      school,
+      course,
+      treasury,
+      nftMint: mint,
+      nftTokenAccount: getAssociatedTokenAddressSync(mint, school, true),
+      metadata: findMetadataPda(mint),
+      masterEdition: findMasterEditionPda(mint),
+      waitlist,
+    };
+    await expectError(
+      program.methods.closeCourse().accountsPartial(closeAccounts).rpc(),
+      "CourseNotEmpty"
+    );
+    await program.methods
+      .dropClass()
+      .accountsPartial({
+        wallet: wallet.publicKey,
+        school,
+        student: findStudentPda(wallet.publicKey),
+        course,
+        enrollment: findCourseEnrollmentPda(course, wallet.publicKey),
+        treasury,
+      })
+      .signers([wallet])
+      .rpc();
+
+    const { classCount, nextCourseIndex } = await program.account.school.fetch(
+      school
+    );
+    const before = await program.account.treasury.fetch(treasury);
+    const treasuryBalance = await provider.connection.getBalance(treasury);
+    await program.methods.closeCourse().accountsPartial(closeAccounts).rpc();
+    assert.isNull(await provider.connection.getAccountInfo(course));
+    // All of the reclaimed rent is accounted for as a treasury deposit.
+    const after = await program.account.treasury.fetch(treasury);
+    assert.equal(
+      after.totalCollected.toNumber() - before.totalCollected.toNumber(),
+      (await provider.connection.getBalance(treasury)) - treasuryBalance
+    );
+    // The course NFT and the waitlist go with the course.
+    assert.isNull(await provider.connection.getAccountInfo(waitlist));
+    assert.isNull(
+      await provider.connection.getAccountInfo(findMetadataPda(mint))
+    );
+    const updated = await program.account.school.fetch(school);
+    assert.equal(updated.classCount.toNumber(), classCount.toNumber() - 1);
+    assert.equal(
+      updated.nextCourseIndex.toNumber(),
+      nextCourseIndex.toNumber()
+    );
+
+    // The next course gets a fresh address rather than reusing a live one.
+    await addCourse("After Close", 1, 0);
+  });
+
+  it("Requires a passing grade in every prerequisite", async () => {
+    const { course: intro } = await addCourse("Intro 101", 5, 0);
+    const { course: advanced } = await addCourse("Advanced 201", 5, 0);
+    const updatePrerequisites = (prerequisites: PublicKey[]) =>
+      program.methods
+        .updatePrerequisites(prerequisites, 60)
+        .accountsPartial({ authority, school, course: advanced })
+        .remainingAccounts(
+          prerequisites.map((pubkey) => ({
+            pubkey,
+            isSigner: false,
+            isWritable: false,
+          }))
+        )
+        .rpc();
+    await expectError(
+      updatePrerequisites([intro, intro]),
+      "InvalidPrerequisite"
+    );
+    await updatePrerequisites([intro]);
+
+    const wallet = await fundedKeypair();
+    await enroll(wallet);
+    await registerForClass(wallet, intro);
+    const student = findStudentPda(wallet.publicKey);
+    const [completionRecord] = PublicKey.findProgramAddressSync(
+      [Buffer.from("completion"), intro.toBuffer(), student.toBuffer()],
+      program.programId
+    );
+    const recordCompletion = (grade: number) =>
+      program.methods
+        .recordCompletion(grade)
+        .accountsPartial({
+          instructor: authority,
+          school,
+          course: intro,
+          student,
+          enrollment: findCourseEnrollmentPda(intro, wallet.publicKey),
+          completionRecord,
+        })
+        .rpc();
+    const registerForAdvanced = (completionRecords: PublicKey[]) =>
+      program.methods
+        .registerForClass()
+        .accountsPartial({
+          wallet: wallet.publicKey,
+          school,
+          student,
+          course: advanced,
+          enrollment: findCourseEnrollmentPda(advanced, wallet.publicKey),
+        })
+        .remainingAccounts(
+          completionRecords.map((pubkey) => ({
+            pubkey,
+            isSigner: false,
+            isWritable: false,
+          }))
+        )
+        .signers([wallet])
+        .rpc();
+
+    await expectError(registerForAdvanced([]), "PrerequisiteNotMet");
+    await recordCompletion(50);
+    await expectError(
+      registerForAdvanced([completionRecord]),
+      "PrerequisiteNotMet"
+    );
+    await recordCompletion(80);
+    await registerForAdvanced([completionRecord]);
+
+    const record = await program.account.completionRecord.fetch(
+      completionRecord
+    );
+    assert.equal(record.grade, 80);
+    const account = await program.account.course.fetch(advanced);
+    assert.equal(account.enrolledStudentsCount, 1);
+
+    // Graded students cannot drop the course for a refund and keep the credit.
+    await expectError(
+      program.methods
+        .dropClass()
+        .accountsPartial({
+          wallet: wallet.publicKey,
+          school,
+          student,
+          course: intro,
+          enrollment: findCourseEnrollmentPda(intro, wallet.publicKey),
+          completionRecord,
+          treasury,
+        })
+        .signers([wallet])
+        .rpc(),
+      "AlreadyCompleted"
+    );
+  });
+
+  it("Completes graded registrations so the course can be closed", async () => {
+    const { course, mint } = await addCourse("Capstone 401", 5, 100_000);
+    const wallet = await fundedKeypair();
+    await enroll(wallet);
+    await registerForClass(wallet, course);
+    const student = findStudentPda(wallet.publicKey);
+    const enrollment = findCourseEnrollmentPda(course, wallet.publicKey);
+    const [completionRecord] = PublicKey.findProgramAddressSync(
+      [Buffer.from("completion"), course.toBuffer(), student.toBuffer()],
+      program.programId
+    );
+    const completeAccounts = {
+      school,
+      course,
+      student,
+      wallet: wallet.publicKey,
+      enrollment,
+      completionRecord,
+      treasury,
+    };
+    await expectError(
+      program.methods
+        .completeEnrollment()
+        .accountsPartial(completeAccounts)
+        .rpc(),
+      "AccountNotInitialized"
+    );
+    await program.methods
+      .recordCompletion(90)
+      .accountsPartial({
+        instructor: authority,
+        school,
+        course,
+        student,
+        enrollment,
+        completionRecord,
+      })
+      .rpc();
+
+    // The tuition still in escrow is released, as the course was completed.
+    const before = await provider.connection.getBalance(treasury);
+    await program.methods
+      .completeEnrollment()
+      .accountsPartial(completeAccounts)
+      .rpc();
+    assert.equal(
+      await provider.connection.getBalance(treasury),
+      before + 100_000
+    );
+    assert.isNull(await provider.connection.getAccountInfo(enrollment));
+    const { enrolledStudentsCount } = await program.account.course.fetch(
+      course
+    );
+    assert.equal(enrolledStudentsCount, 0);
 
-  it("Is initialized!", async () => {
-    // Add your test here.
-    const tx = await program.methods.initialize().rpc();
-    console.log("Your transaction signature", tx);
+    await program.methods
+      .closeCourse()
+      .accountsPartial({
+        authority,
+        school,
+        course,
+        treasury,
+        nftMint: mint,
+        nftTokenAccount: getAssociatedTokenAddressSync(mint, school, true),
+        metadata: findMetadataPda(mint),
+        masterEdition: findMasterEditionPda(mint),
+        waitlist: PublicKey.findProgramAddressSync(
+          [Buffer.from("waitlist"), course.toBuffer()],
+          program.programId
+        )[0],
+      })
+      .rpc();
+    assert.isNull(await provider.connection.getAccountInfo(course));
+    // The grade outlives the course, for later prerequisite checks.
+    const record = await program.account.completionRecord.fetch(
+      completionRecord
+    );
+    assert.equal(record.grade, 90);
   });
 });